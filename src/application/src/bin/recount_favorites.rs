@@ -0,0 +1,22 @@
+use db::Repository;
+use realworld_application::configuration::Settings;
+use std::path::PathBuf;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Recomputes `articles.favorites_count` from the `favorites` table.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let settings = Settings::new(PathBuf::default()).expect("Failed to load configuration");
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(
+            std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
+        ))
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+    let repository = Repository::create(settings.database.connection_string()).await?;
+    let fixed = repository.recount_favorites().await?;
+    tracing::info!("fixed the favorites count of {} articles", fixed);
+
+    Ok(())
+}
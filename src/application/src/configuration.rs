@@ -1,7 +1,9 @@
 use config::{Config, ConfigError, Environment, File};
 use serde::Deserialize;
-use std::env;
 use std::path::PathBuf;
+use std::time::Duration;
+use std::{env, fs, io};
+use web2::settings::Algorithm;
 
 #[derive(Debug, Deserialize)]
 pub struct Application {
@@ -37,7 +39,75 @@ impl Postgres {
 
 #[derive(Debug, Deserialize)]
 pub struct Authentication {
-    pub secret: String,
+    pub algorithm: Algorithm,
+    /// Shared secret signing and verifying tokens with HS256.
+    pub secret: Option<String>,
+    /// Path to the PEM encoded private key signing tokens with RS256 or EdDSA.
+    pub private_key: Option<PathBuf>,
+    /// Path to the PEM encoded public key matching `private_key`.
+    pub public_key: Option<PathBuf>,
+    /// Keys replaced by `secret` or `public_key` whose tokens are still accepted:
+    /// secrets with HS256, paths to PEM encoded public keys otherwise.
+    #[serde(default)]
+    pub previous_keys: Vec<String>,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    /// Access token lifetime, in seconds.
+    pub lifetime: u64,
+    /// Refresh token lifetime, in seconds.
+    pub session_lifetime: u64,
+}
+
+impl Authentication {
+    /// Loads the keys, reading them from disk for RS256 and EdDSA.
+    pub fn web_settings(&self) -> io::Result<web2::settings::Authentication> {
+        let (signing_key, verification_keys) = match self.algorithm {
+            Algorithm::HS256 => {
+                let secret = required(&self.secret, "authentication.secret")?;
+                let mut keys = vec![secret.clone()];
+                keys.extend(self.previous_keys.iter().cloned());
+                (secret.clone(), keys)
+            }
+            Algorithm::RS256 | Algorithm::EdDSA => {
+                let private_key = required(&self.private_key, "authentication.private_key")?;
+                let public_key = required(&self.public_key, "authentication.public_key")?;
+                let mut keys = vec![fs::read_to_string(public_key)?];
+                for path in &self.previous_keys {
+                    keys.push(fs::read_to_string(path)?);
+                }
+                (fs::read_to_string(private_key)?, keys)
+            }
+        };
+        Ok(web2::settings::Authentication {
+            algorithm: self.algorithm,
+            signing_key,
+            verification_keys,
+            issuer: self.issuer.clone(),
+            audience: self.audience.clone(),
+            token_lifetime: Duration::from_secs(self.lifetime),
+            session_lifetime: Duration::from_secs(self.session_lifetime),
+        })
+    }
+}
+
+fn required<'a, T>(value: &'a Option<T>, name: &str) -> io::Result<&'a T> {
+    value.as_ref().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("`{}` is required by the configured algorithm", name),
+        )
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Pagination {
+    pub default_limit: u64,
+    pub max_limit: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Articles {
+    pub slug_follows_title: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -45,6 +115,8 @@ pub struct Settings {
     pub application: Application,
     pub database: Postgres,
     pub authentication: Authentication,
+    pub pagination: Pagination,
+    pub articles: Articles,
 }
 
 impl Settings {
@@ -59,7 +131,7 @@ impl Settings {
         let environment = env::var("APP_ENVIRONMENT").unwrap_or_else(|_| "development".into());
 
         // Add in environment-specific settings (optional)
-        let path = base_path.join(&format!("configuration/{}", environment));
+        let path = base_path.join(format!("configuration/{}", environment));
         s.merge(File::from(path).required(false))?;
 
         // Add in settings from environment variables (with a prefix of APP and '_' as separator)
@@ -69,4 +141,18 @@ impl Settings {
         // Deserialize (and thus freeze) the entire configuration as
         s.try_into()
     }
+
+    /// Settings of the web application.
+    pub fn web_settings(&self) -> io::Result<web2::settings::Settings> {
+        Ok(web2::settings::Settings {
+            pagination: web2::settings::Pagination {
+                default_limit: self.pagination.default_limit,
+                max_limit: self.pagination.max_limit,
+            },
+            authentication: self.authentication.web_settings()?,
+            articles: web2::settings::Articles {
+                slug_follows_title: self.articles.slug_follows_title,
+            },
+        })
+    }
 }
@@ -19,7 +19,10 @@ async fn main() -> Result<(), std::io::Error> {
         .await
         .expect("Failed to create repository");
 
-    let app = get_app(state);
+    let web_settings = settings
+        .web_settings()
+        .expect("Failed to load authentication keys");
+    let app = get_app(state, web_settings);
     let address: SocketAddr = format!(
         "{}:{}",
         settings.application.host, settings.application.port
@@ -0,0 +1,10 @@
+DROP TABLE article_slug_history;
+
+ALTER TABLE comments
+    DROP CONSTRAINT comments_article_id_fkey,
+    ADD CONSTRAINT comments_article_id_fkey FOREIGN KEY (article_id)
+        REFERENCES articles(slug) ON DELETE CASCADE;
+ALTER TABLE favorites
+    DROP CONSTRAINT favorites_article_id_fkey,
+    ADD CONSTRAINT favorites_article_id_fkey FOREIGN KEY (article_id)
+        REFERENCES articles(slug) ON DELETE CASCADE;
@@ -0,0 +1,18 @@
+-- Slugs follow the title of their article: references to an article follow its slug
+ALTER TABLE favorites
+    DROP CONSTRAINT favorites_article_id_fkey,
+    ADD CONSTRAINT favorites_article_id_fkey FOREIGN KEY (article_id)
+        REFERENCES articles(slug) ON DELETE CASCADE ON UPDATE CASCADE;
+ALTER TABLE comments
+    DROP CONSTRAINT comments_article_id_fkey,
+    ADD CONSTRAINT comments_article_id_fkey FOREIGN KEY (article_id)
+        REFERENCES articles(slug) ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- Slugs articles used before being renamed, so that old links keep working
+CREATE TABLE article_slug_history (
+    slug VARCHAR(255) PRIMARY KEY,
+    article_slug VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (article_slug) REFERENCES articles(slug) ON DELETE CASCADE ON UPDATE CASCADE
+);
+CREATE INDEX article_slug_history_article_slug_idx ON article_slug_history (article_slug);
@@ -0,0 +1,34 @@
+ALTER TABLE favorites ADD COLUMN article_slug VARCHAR(255);
+UPDATE favorites SET article_slug = articles.slug FROM articles WHERE articles.id = favorites.article_id;
+ALTER TABLE comments ADD COLUMN article_slug VARCHAR(255);
+UPDATE comments SET article_slug = articles.slug FROM articles WHERE articles.id = comments.article_id;
+ALTER TABLE article_slug_history ADD COLUMN article_slug VARCHAR(255);
+UPDATE article_slug_history SET article_slug = articles.slug
+    FROM articles WHERE articles.id = article_slug_history.article_id;
+
+ALTER TABLE favorites DROP CONSTRAINT favorites_pkey, DROP COLUMN article_id;
+ALTER TABLE comments DROP COLUMN article_id;
+ALTER TABLE article_slug_history DROP COLUMN article_id;
+
+ALTER TABLE articles DROP CONSTRAINT articles_slug_key;
+ALTER TABLE articles DROP CONSTRAINT articles_pkey;
+ALTER TABLE articles ADD PRIMARY KEY (slug);
+ALTER TABLE articles DROP COLUMN id;
+
+ALTER TABLE favorites RENAME COLUMN article_slug TO article_id;
+ALTER TABLE favorites
+    ALTER COLUMN article_id SET NOT NULL,
+    ADD PRIMARY KEY (user_id, article_id),
+    ADD CONSTRAINT favorites_article_id_fkey FOREIGN KEY (article_id)
+        REFERENCES articles(slug) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE comments RENAME COLUMN article_slug TO article_id;
+ALTER TABLE comments
+    ALTER COLUMN article_id SET NOT NULL,
+    ADD CONSTRAINT comments_article_id_fkey FOREIGN KEY (article_id)
+        REFERENCES articles(slug) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE article_slug_history
+    ALTER COLUMN article_slug SET NOT NULL,
+    ADD FOREIGN KEY (article_slug) REFERENCES articles(slug) ON DELETE CASCADE ON UPDATE CASCADE;
+CREATE INDEX article_slug_history_article_slug_idx ON article_slug_history (article_slug);
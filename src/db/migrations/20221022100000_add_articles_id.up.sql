@@ -0,0 +1,37 @@
+-- Articles are identified by a surrogate key: the slug is only used for lookups
+ALTER TABLE articles ADD COLUMN id UUID NOT NULL DEFAULT gen_random_uuid();
+ALTER TABLE articles ALTER COLUMN id DROP DEFAULT;
+
+ALTER TABLE favorites ADD COLUMN new_article_id UUID;
+UPDATE favorites SET new_article_id = articles.id FROM articles WHERE articles.slug = favorites.article_id;
+ALTER TABLE comments ADD COLUMN new_article_id UUID;
+UPDATE comments SET new_article_id = articles.id FROM articles WHERE articles.slug = comments.article_id;
+ALTER TABLE article_slug_history ADD COLUMN article_id UUID;
+UPDATE article_slug_history SET article_id = articles.id
+    FROM articles WHERE articles.slug = article_slug_history.article_slug;
+
+ALTER TABLE favorites DROP CONSTRAINT favorites_pkey, DROP COLUMN article_id;
+ALTER TABLE comments DROP COLUMN article_id;
+ALTER TABLE article_slug_history DROP COLUMN article_slug;
+
+ALTER TABLE articles DROP CONSTRAINT articles_pkey;
+ALTER TABLE articles ADD PRIMARY KEY (id);
+ALTER TABLE articles ADD CONSTRAINT articles_slug_key UNIQUE (slug);
+
+ALTER TABLE favorites RENAME COLUMN new_article_id TO article_id;
+ALTER TABLE favorites
+    ALTER COLUMN article_id SET NOT NULL,
+    ADD PRIMARY KEY (user_id, article_id),
+    ADD FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE;
+CREATE INDEX favorites_article_id_idx ON favorites (article_id);
+
+ALTER TABLE comments RENAME COLUMN new_article_id TO article_id;
+ALTER TABLE comments
+    ALTER COLUMN article_id SET NOT NULL,
+    ADD FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE;
+CREATE INDEX comments_article_id_idx ON comments (article_id);
+
+ALTER TABLE article_slug_history
+    ALTER COLUMN article_id SET NOT NULL,
+    ADD FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE;
+CREATE INDEX article_slug_history_article_id_idx ON article_slug_history (article_id);
@@ -0,0 +1,24 @@
+-- Tags get a table of their own, indexing `articles.tag_list` which articles are still read
+-- from: both are only ever written together. Names are not limited in length, as tags
+-- saved before their length was checked are copied as they are.
+CREATE TABLE tags (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT tags_name_key UNIQUE (name)
+);
+
+CREATE TABLE article_tags (
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (article_id, tag_id)
+);
+CREATE INDEX article_tags_tag_id_idx ON article_tags (tag_id);
+
+INSERT INTO tags (name)
+    SELECT DISTINCT jsonb_array_elements_text(tag_list) FROM articles
+    ON CONFLICT (name) DO NOTHING;
+INSERT INTO article_tags (article_id, tag_id)
+    SELECT DISTINCT articles.id, tags.id
+    FROM articles, jsonb_array_elements_text(articles.tag_list) AS tag(name)
+    JOIN tags ON tags.name = tag.name;
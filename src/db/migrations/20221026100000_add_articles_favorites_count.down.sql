@@ -0,0 +1,2 @@
+DROP INDEX articles_favorites_count_idx;
+ALTER TABLE articles DROP COLUMN favorites_count;
@@ -0,0 +1,6 @@
+-- Kept in step with `favorites` by the repository, in the same transaction
+ALTER TABLE articles ADD COLUMN favorites_count BIGINT NOT NULL DEFAULT 0;
+UPDATE articles SET favorites_count = counts.count
+    FROM (SELECT article_id, count(*) AS count FROM favorites GROUP BY article_id) AS counts
+    WHERE counts.article_id = articles.id;
+CREATE INDEX articles_favorites_count_idx ON articles (favorites_count DESC, created_at DESC, id);
@@ -0,0 +1,4 @@
+DROP INDEX articles_comments_count_idx;
+DROP INDEX articles_updated_at_idx;
+DROP INDEX articles_created_at_idx;
+ALTER TABLE articles DROP COLUMN comments_count;
@@ -0,0 +1,10 @@
+-- Every order articles can be listed in is backed by an index
+ALTER TABLE articles ADD COLUMN comments_count BIGINT NOT NULL DEFAULT 0;
+UPDATE articles SET comments_count = counts.count
+    FROM (SELECT article_id, count(*) AS count FROM comments GROUP BY article_id) AS counts
+    WHERE counts.article_id = articles.id;
+
+-- Scanned backwards for the oldest articles first
+CREATE INDEX articles_created_at_idx ON articles (created_at DESC, id);
+CREATE INDEX articles_updated_at_idx ON articles (updated_at DESC, created_at DESC, id);
+CREATE INDEX articles_comments_count_idx ON articles (comments_count DESC, created_at DESC, id);
@@ -0,0 +1,8 @@
+-- Full-text search: matches in titles weigh more than matches in descriptions, and these
+-- more than matches in bodies
+ALTER TABLE articles ADD COLUMN search TSVECTOR GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', title), 'A') ||
+    setweight(to_tsvector('english', description), 'B') ||
+    setweight(to_tsvector('english', body), 'C')
+) STORED;
+CREATE INDEX articles_search_idx ON articles USING GIN (search);
@@ -0,0 +1,3 @@
+-- Comments can be edited from now on: only edits set `edited_at`, so the comments posted
+-- so far are not edited whatever their `updated_at`
+ALTER TABLE comments ADD COLUMN edited_at TIMESTAMPTZ;
@@ -0,0 +1,7 @@
+DROP INDEX comments_parent_id_idx;
+
+DELETE FROM comments WHERE deleted;
+
+ALTER TABLE comments
+    DROP COLUMN deleted,
+    DROP COLUMN parent_id;
@@ -0,0 +1,7 @@
+-- Replies point to the comment they answer. Comments with replies are not
+-- deleted but emptied and flagged, so that the thread stays in one piece.
+ALTER TABLE comments
+    ADD COLUMN parent_id BIGINT REFERENCES comments (id) ON DELETE SET NULL,
+    ADD COLUMN deleted BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX comments_parent_id_idx ON comments (parent_id);
@@ -0,0 +1 @@
+DROP INDEX comments_threads_idx;
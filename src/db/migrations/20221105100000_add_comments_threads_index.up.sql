@@ -0,0 +1,3 @@
+-- Pages of comments are made of threads: this index walks the top-level
+-- comments of an article in either order, from any cursor.
+CREATE INDEX comments_threads_idx ON comments (article_id, id) WHERE parent_id IS NULL;
@@ -0,0 +1 @@
+-- Normalized tags are valid tags: there is nothing to undo
@@ -0,0 +1,29 @@
+-- Tags are now trimmed and lowercased when saved and when filtering: normalize those saved
+-- before, merging the ones that become the same
+UPDATE articles SET tag_list = normalized.tag_list
+FROM (
+    SELECT articles.id, COALESCE(
+        jsonb_agg(DISTINCT lower(btrim(tag.name, E' \t\r\n'))) FILTER (
+            WHERE btrim(tag.name, E' \t\r\n') <> ''
+        ),
+        '[]'::jsonb
+    ) AS tag_list
+    FROM articles
+    LEFT JOIN jsonb_array_elements_text(articles.tag_list) AS tag(name) ON true
+    GROUP BY articles.id
+) AS normalized
+WHERE articles.id = normalized.id AND articles.tag_list <> normalized.tag_list;
+
+INSERT INTO tags (name)
+    SELECT DISTINCT lower(btrim(name, E' \t\r\n')) FROM tags
+    WHERE btrim(name, E' \t\r\n') <> ''
+    ON CONFLICT (name) DO NOTHING;
+INSERT INTO article_tags (article_id, tag_id)
+    SELECT article_tags.article_id, normalized.id
+    FROM article_tags
+    JOIN tags ON tags.id = article_tags.tag_id
+    JOIN tags AS normalized ON normalized.name = lower(btrim(tags.name, E' \t\r\n'))
+    WHERE normalized.id <> tags.id
+    ON CONFLICT DO NOTHING;
+-- Also drops their rows in article_tags
+DELETE FROM tags WHERE name <> lower(btrim(name, E' \t\r\n'));
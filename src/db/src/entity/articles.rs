@@ -7,8 +7,10 @@ use crate::json::Json as ModelJson;
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "articles")]
 pub struct Model {
-    pub title: String,
     #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub title: String,
+    #[sea_orm(unique)]
     pub slug: String,
     pub description: String,
     #[sea_orm(column_type = "Text")]
@@ -18,6 +20,8 @@ pub struct Model {
     pub user_id: Uuid,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub favorites_count: i64,
+    pub comments_count: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -34,6 +38,10 @@ pub enum Relation {
     Favorites,
     #[sea_orm(has_many = "super::comments::Entity")]
     Comments,
+    #[sea_orm(has_many = "super::article_slug_history::Entity")]
+    ArticleSlugHistory,
+    #[sea_orm(has_many = "super::article_tags::Entity")]
+    ArticleTags,
 }
 
 impl Related<super::users::Entity> for Entity {
@@ -54,4 +62,16 @@ impl Related<super::comments::Entity> for Entity {
     }
 }
 
+impl Related<super::article_slug_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ArticleSlugHistory.def()
+    }
+}
+
+impl Related<super::article_tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ArticleTags.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
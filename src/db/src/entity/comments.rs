@@ -8,11 +8,14 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64,
     pub author_id: Uuid,
-    pub article_id: String,
+    pub article_id: Uuid,
     #[sea_orm(column_type = "Text")]
     pub body: String,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub edited_at: Option<DateTimeWithTimeZone>,
+    pub parent_id: Option<i64>,
+    pub deleted: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -20,7 +23,7 @@ pub enum Relation {
     #[sea_orm(
         belongs_to = "super::articles::Entity",
         from = "Column::ArticleId",
-        to = "super::articles::Column::Slug",
+        to = "super::articles::Column::Id",
         on_update = "NoAction",
         on_delete = "Cascade"
     )]
@@ -33,6 +36,14 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Users,
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentId",
+        to = "Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    SelfRef,
 }
 
 impl Related<super::articles::Entity> for Entity {
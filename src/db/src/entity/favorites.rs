@@ -8,7 +8,7 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub user_id: Uuid,
     #[sea_orm(primary_key, auto_increment = false)]
-    pub article_id: String,
+    pub article_id: Uuid,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -16,7 +16,7 @@ pub enum Relation {
     #[sea_orm(
         belongs_to = "super::articles::Entity",
         from = "Column::ArticleId",
-        to = "super::articles::Column::Slug",
+        to = "super::articles::Column::Id",
         on_update = "NoAction",
         on_delete = "Cascade"
     )]
@@ -2,8 +2,12 @@
 
 pub mod prelude;
 
+pub mod article_slug_history;
+pub mod article_tags;
 pub mod articles;
 pub mod comments;
 pub mod favorites;
 pub mod followers;
+pub mod sessions;
+pub mod tags;
 pub mod users;
@@ -1,7 +1,11 @@
 //! SeaORM Entity. Generated by sea-orm-codegen 0.7.0
 
+pub use super::article_slug_history::Entity as ArticleSlugHistory;
+pub use super::article_tags::Entity as ArticleTags;
 pub use super::articles::Entity as Articles;
 pub use super::comments::Entity as Comments;
 pub use super::favorites::Entity as Favorites;
 pub use super::followers::Entity as Followers;
+pub use super::sessions::Entity as Sessions;
+pub use super::tags::Entity as Tags;
 pub use super::users::Entity as Users;
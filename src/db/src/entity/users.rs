@@ -26,6 +26,8 @@ pub enum Relation {
     Favorites,
     #[sea_orm(has_many = "super::comments::Entity")]
     Comments,
+    #[sea_orm(has_many = "super::sessions::Entity")]
+    Sessions,
 }
 
 impl Related<super::articles::Entity> for Entity {
@@ -46,4 +48,10 @@ impl Related<super::comments::Entity> for Entity {
     }
 }
 
+impl Related<super::sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sessions.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
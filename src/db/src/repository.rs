@@ -4,19 +4,25 @@ use std::collections::HashSet;
 use crate::json::Json;
 use crate::shims::to_article;
 use crate::shims::to_comment;
-use chrono::Utc;
-use realworld_domain::{Article, FavoriteOutcome};
-use sea_orm::sea_query::Expr;
+use chrono::{DateTime, Utc};
+use realworld_domain::{
+    slug_candidates, Article, ArticleSort, FavoriteOutcome, FeedPage, Page, TagMatch,
+};
+use sea_orm::sea_query::{Expr, Value};
+use sea_orm::Condition;
+use sea_orm::ConnectionTrait;
 use sea_orm::DbBackend;
 use sea_orm::DeleteResult;
 use sea_orm::JoinType;
+use sea_orm::SelectTwo;
 use sea_orm::Statement;
+use serde_json::json;
 
 use sea_orm::FromQueryResult;
 use sea_orm::ModelTrait;
 use sea_orm::{
     ActiveModelTrait, ActiveValue, ColumnTrait, Database, DatabaseConnection, EntityTrait,
-    QueryFilter, QuerySelect,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
 };
 use uuid::Uuid;
 
@@ -46,46 +52,81 @@ impl realworld_domain::repositories::Repository for Repository {
     ) -> Result<realworld_domain::Article, realworld_domain::PublishArticleError> {
         use crate::entity::articles;
 
-        let article = articles::ActiveModel {
-            title: ActiveValue::Set(draft.title.clone()),
-            body: ActiveValue::Set(draft.body.clone()),
-            description: ActiveValue::Set(draft.description.clone()),
-            created_at: ActiveValue::Set(Utc::now().into()),
-            updated_at: ActiveValue::Set(Utc::now().into()),
-            user_id: ActiveValue::Set(author.id),
-            slug: ActiveValue::Set(draft.slug()),
-            tag_list: ActiveValue::Set(Json(draft.tag_list)),
-        };
-        article
-            .insert(&self.0)
-            .await
-            .map(|article| to_article(article, author.clone(), 0))
-            .map_err(to_db_error)
-            .map(Ok)?
+        let mut last_slug = draft.slug();
+        // Titles are not unique: try the next candidate until a slug is free. Candidates never
+        // run out, so the error below is only a safeguard
+        for slug in draft.slug_candidates() {
+            if !slug_is_available(&self.0, &slug, None)
+                .await
+                .map_err(to_db_error)?
+            {
+                last_slug = slug;
+                continue;
+            }
+            let article = articles::ActiveModel {
+                id: ActiveValue::Set(Uuid::new_v4()),
+                title: ActiveValue::Set(draft.title.clone()),
+                body: ActiveValue::Set(draft.body.clone()),
+                description: ActiveValue::Set(draft.description.clone()),
+                created_at: ActiveValue::Set(Utc::now().into()),
+                updated_at: ActiveValue::Set(Utc::now().into()),
+                user_id: ActiveValue::Set(author.id),
+                slug: ActiveValue::Set(slug.clone()),
+                tag_list: ActiveValue::Set(Json(draft.tag_list.clone())),
+                favorites_count: ActiveValue::Set(0),
+                comments_count: ActiveValue::Set(0),
+            };
+            let txn = self.0.begin().await.map_err(to_db_error)?;
+            match article.insert(&txn).await {
+                Ok(article) => {
+                    set_article_tags(&txn, article.id, &draft.tag_list)
+                        .await
+                        .map_err(to_db_error)?;
+                    txn.commit().await.map_err(to_db_error)?;
+                    return Ok(to_article(article, author.clone()));
+                }
+                Err(e) if violated_unique_constraint(&e) == Some("articles_slug_key") => {
+                    txn.rollback().await.map_err(to_db_error)?;
+                    last_slug = slug;
+                }
+                Err(e) => return Err(to_db_error(e).into()),
+            }
+        }
+        Err(realworld_domain::PublishArticleError::DuplicatedSlug {
+            slug: last_slug,
+            source: anyhow::anyhow!("Every slug candidate is taken").into(),
+        })
     }
 
     async fn get_article_by_slug(
         &self,
         slug: &str,
     ) -> Result<realworld_domain::Article, realworld_domain::GetArticleError> {
-        use crate::entity::articles::Entity as Article;
-        use crate::entity::users;
-        let mut article = Article::find_by_id(slug.to_string())
-            .find_also_related(users::Entity)
-            .one(&self.0)
+        let article = find_article_by_slug(&self.0, slug)
             .await
             .map_err(to_db_error)?
-            .and_then(|(article, user)| user.map(|u| to_article(article, u.into(), 0)))
-            .ok_or_else(|| {
-                realworld_domain::DatabaseError::from(anyhow::anyhow!("Article not found"))
+            .ok_or_else(|| realworld_domain::GetArticleError::ArticleNotFound {
+                slug: slug.to_string(),
+                source: anyhow::anyhow!("Article not found").into(),
             })?;
 
-        let n_fav = self.n_favorites(&article).await?;
-        article.favorites_count = n_fav as u64;
-
         Ok(article)
     }
 
+    async fn get_current_slug(
+        &self,
+        old_slug: &str,
+    ) -> Result<Option<String>, realworld_domain::DatabaseError> {
+        use crate::entity::article_slug_history::Entity as ArticleSlugHistory;
+        use crate::entity::articles;
+        let entry = ArticleSlugHistory::find_by_id(old_slug.to_string())
+            .find_also_related(articles::Entity)
+            .one(&self.0)
+            .await
+            .map_err(to_db_error)?;
+        Ok(entry.and_then(|(_, article)| article).map(|a| a.slug))
+    }
+
     async fn get_article_view(
         &self,
         viewer: &realworld_domain::User,
@@ -96,6 +137,7 @@ impl realworld_domain::repositories::Repository for Repository {
             .await?;
         let is_favorite = self.is_favorite(&article, viewer).await?;
         let article_view = realworld_domain::ArticleView {
+            id: article.id,
             content: article.content,
             slug: article.slug,
             author: author_view,
@@ -113,92 +155,204 @@ impl realworld_domain::repositories::Repository for Repository {
         articles: Vec<realworld_domain::Article>,
     ) -> Result<Vec<realworld_domain::ArticleView>, realworld_domain::DatabaseError> {
         let are_favorites = self.are_favorites(&articles, viewer).await?;
-        let mut articles_view = Vec::with_capacity(are_favorites.len());
-        for article in articles.iter() {
-            let author_view = self
-                .get_profile_view(viewer, &article.author.username)
-                .await?;
-
-            let favorited = are_favorites
-                .get(article.slug.as_str())
-                .cloned()
-                .unwrap_or_default();
-            let article_view = realworld_domain::ArticleView {
-                content: article.content.clone(),
-                slug: article.slug.clone(),
-                author: author_view,
-                metadata: article.metadata.clone(),
-                favorited,
-                favorites_count: article.favorites_count,
-                viewer: viewer.id,
-            };
-            articles_view.push(article_view);
-        }
+        let authors = articles
+            .iter()
+            .map(|a| a.author.username.as_str())
+            .collect();
+        let followed = self.followed_authors(authors, viewer).await?;
+        let articles_view = articles
+            .into_iter()
+            .map(|article| {
+                let author = realworld_domain::ProfileView {
+                    following: followed.contains(&article.author.username),
+                    profile: article.author,
+                    viewer: viewer.id,
+                };
+                realworld_domain::ArticleView {
+                    favorited: are_favorites.get(&article.id).cloned().unwrap_or_default(),
+                    id: article.id,
+                    content: article.content,
+                    slug: article.slug,
+                    author,
+                    metadata: article.metadata,
+                    favorites_count: article.favorites_count,
+                    viewer: viewer.id,
+                }
+            })
+            .collect();
         Ok(articles_view)
     }
 
     async fn find_articles(
         &self,
         query: realworld_domain::ArticleQuery,
-    ) -> Result<Vec<realworld_domain::Article>, realworld_domain::DatabaseError> {
-        use crate::entity::articles::Entity as Article;
-        use crate::entity::users;
+    ) -> Result<Page<realworld_domain::Article>, realworld_domain::DatabaseError> {
+        use crate::entity::articles;
 
-        let mut q = Article::find().find_also_related(users::Entity);
+        let mut q = match self.filter_articles(&query).await? {
+            Some(q) => q,
+            None => return Ok(Page::new(vec![], 0)),
+        };
 
-        if let Some(username) = query.author {
-            q = q.filter(users::Column::Username.eq(username));
-        }
+        let total = q.clone().count(&self.0).await.map_err(to_db_error)? as u64;
 
-        if let Some(tag) = query.tag {
-            q = q.filter(Expr::cust(&format!("tag_list @> '\"{}\"'", tag)));
+        q = match query.sort {
+            ArticleSort::Recent => q,
+            ArticleSort::Oldest => q
+                .order_by_asc(articles::Column::CreatedAt)
+                .order_by_desc(articles::Column::Id),
+            ArticleSort::MostFavorited => q.order_by_desc(articles::Column::FavoritesCount),
+            ArticleSort::MostCommented => q.order_by_desc(articles::Column::CommentsCount),
+            ArticleSort::Updated => q.order_by_desc(articles::Column::UpdatedAt),
+        };
+        // Ties are broken by recency, matching the indexes on `articles`
+        q = q
+            .order_by_desc(articles::Column::CreatedAt)
+            .order_by_asc(articles::Column::Id);
+        if let Some(limit) = query.limit {
+            q = q.limit(limit);
+        }
+        if let Some(offset) = query.offset {
+            q = q.offset(offset);
         }
 
-        let mut articles: Vec<realworld_domain::Article> = q
+        let articles: Vec<realworld_domain::Article> = q
             .all(&self.0)
             .await
             .map_err(to_db_error)?
             .into_iter()
             .filter_map(|(article, user)| user.map(|u| (article, realworld_domain::User::from(u))))
-            .map(|(article, user)| to_article(article, user, 0))
+            .map(|(article, user)| to_article(article, user))
             .collect();
 
-        // TODO Optimize
-        for article in articles.iter_mut() {
-            let n_fav = self.n_favorites(article).await?;
-            article.favorites_count = n_fav as u64;
+        Ok(Page::new(articles, total))
+    }
+
+    async fn search_articles(
+        &self,
+        text: &realworld_domain::SearchText,
+        query: realworld_domain::ArticleQuery,
+    ) -> Result<Page<realworld_domain::SearchHit>, realworld_domain::DatabaseError> {
+        use crate::entity::articles::{self, Entity as Article};
+        #[derive(FromQueryResult)]
+        struct Snippet {
+            id: Uuid,
+            snippet: String,
+        }
+
+        let mut q = match self.filter_articles(&query).await? {
+            Some(q) => q,
+            None => return Ok(Page::new(vec![], 0)),
+        };
+        // `websearch_to_tsquery` understands "quoted phrases", `or` and `-excluded` words
+        let matches = |sql: &str| {
+            let sql = sql.replace("QUERY", "websearch_to_tsquery('english', ?)");
+            Expr::cust_with_values(&sql, [text.0.clone()])
+        };
+        q = q.filter(matches("articles.search @@ QUERY"));
+        let total = q.clone().count(&self.0).await.map_err(to_db_error)? as u64;
+
+        q = q
+            .order_by_desc(matches("ts_rank(articles.search, QUERY)"))
+            .order_by_desc(articles::Column::CreatedAt)
+            .order_by_asc(articles::Column::Id);
+        if let Some(limit) = query.limit {
+            q = q.limit(limit);
+        }
+        if let Some(offset) = query.offset {
+            q = q.offset(offset);
         }
-        Ok(articles)
+        let articles: Vec<realworld_domain::Article> = q
+            .all(&self.0)
+            .await
+            .map_err(to_db_error)?
+            .into_iter()
+            .filter_map(|(article, user)| user.map(|u| to_article(article, u.into())))
+            .collect();
+        if articles.is_empty() {
+            return Ok(Page::new(vec![], total));
+        }
+
+        let options = "StartSel=<mark>, StopSel=</mark>, MinWords=15, MaxWords=35, \
+            MaxFragments=2, FragmentDelimiter=\" … \"";
+        // The text is escaped first, so that only the highlighting is markup: Postgres parses
+        // the entities as single tokens, leaving the matching words untouched.
+        let headline = format!(
+            "ts_headline('english', {}, websearch_to_tsquery('english', ?), ?)",
+            escape_html("articles.description || ' ' || articles.body")
+        );
+        let mut snippets: HashMap<Uuid, String> = Article::find()
+            .select_only()
+            .column(articles::Column::Id)
+            .column_as(
+                Expr::cust_with_values(&headline, [text.0.as_str(), options]),
+                "snippet",
+            )
+            .filter(articles::Column::Id.is_in(articles.iter().map(|a| a.id)))
+            .into_model::<Snippet>()
+            .all(&self.0)
+            .await
+            .map_err(to_db_error)?
+            .into_iter()
+            .map(|s| (s.id, s.snippet))
+            .collect();
+        let hits = articles
+            .into_iter()
+            .map(|article| realworld_domain::SearchHit {
+                snippet: snippets.remove(&article.id).unwrap_or_default(),
+                article,
+            })
+            .collect();
+        Ok(Page::new(hits, total))
     }
 
     async fn feed(
         &self,
         user: &realworld_domain::User,
-        _query: realworld_domain::FeedQuery,
-    ) -> Result<Vec<realworld_domain::ArticleView>, realworld_domain::DatabaseError> {
-        use crate::entity::articles::Entity as Articles;
+        query: realworld_domain::FeedQuery,
+    ) -> Result<FeedPage, realworld_domain::DatabaseError> {
+        use crate::entity::articles::{self, Entity as Articles};
         use crate::entity::followers;
         use crate::entity::users;
         use sea_orm::RelationTrait;
 
-        let mut articles: Vec<realworld_domain::Article> = Articles::find()
+        let q = Articles::find()
             .find_also_related(users::Entity)
-            .join_rev(JoinType::InnerJoin, followers::Relation::Users1.def())
-            .filter(followers::Column::FollowerId.eq(user.id))
+            .join_rev(JoinType::InnerJoin, followers::Relation::Users2.def())
+            .filter(followers::Column::FollowerId.eq(user.id));
+
+        let total = q.clone().count(&self.0).await.map_err(to_db_error)? as u64;
+
+        // Keyset pagination: the page starts right after the cursor in the
+        // (created_at DESC, id) order, wherever new articles got published
+        let q = match query.cursor {
+            Some(cursor) => q.filter(
+                Condition::any()
+                    .add(articles::Column::CreatedAt.lt(cursor.created_at))
+                    .add(
+                        Condition::all()
+                            .add(articles::Column::CreatedAt.eq(cursor.created_at))
+                            .add(articles::Column::Id.gt(cursor.id)),
+                    ),
+            ),
+            None => q,
+        };
+        // One more article tells whether there is a next page
+        let articles: Vec<realworld_domain::Article> = q
+            .order_by_desc(articles::Column::CreatedAt)
+            .order_by_asc(articles::Column::Id)
+            .limit(query.limit + 1)
+            .offset(query.offset)
             .all(&self.0)
             .await
             .map_err(to_db_error)?
             .into_iter()
             .filter_map(|(article, user)| user.map(|u| (article, realworld_domain::User::from(u))))
-            .map(|(article, user)| to_article(article, user, 0))
+            .map(|(article, user)| to_article(article, user))
             .collect();
 
-        for article in articles.iter_mut() {
-            let n_fav = self.n_favorites(article).await?;
-            article.favorites_count = n_fav as u64;
-        }
-
-        self.get_articles_views(user, articles).await
+        let views = self.get_articles_views(user, articles).await?;
+        Ok(FeedPage::new(views, query.limit, total))
     }
 
     async fn delete_article(
@@ -207,7 +361,7 @@ impl realworld_domain::repositories::Repository for Repository {
     ) -> Result<(), realworld_domain::DatabaseError> {
         use crate::entity::articles::Entity as Article;
 
-        let article = Article::find_by_id(article.slug.clone())
+        let article = Article::find_by_id(article.id)
             .one(&self.0)
             .await
             .map_err(to_db_error)?
@@ -225,39 +379,44 @@ impl realworld_domain::repositories::Repository for Repository {
         user: &realworld_domain::User,
         article: &realworld_domain::Article,
         comment: realworld_domain::CommentContent,
+        parent_id: Option<u64>,
     ) -> Result<realworld_domain::Comment, realworld_domain::DatabaseError> {
         use crate::entity::comments::ActiveModel;
+        let now = Utc::now();
         let new_comment = ActiveModel {
             body: ActiveValue::Set(comment.0),
-            article_id: ActiveValue::Set(article.slug.clone()),
+            article_id: ActiveValue::Set(article.id),
             author_id: ActiveValue::Set(user.id),
-            created_at: ActiveValue::Set(Utc::now().into()),
-            updated_at: ActiveValue::Set(Utc::now().into()),
+            created_at: ActiveValue::Set(now.into()),
+            updated_at: ActiveValue::Set(now.into()),
+            edited_at: ActiveValue::Set(None),
+            parent_id: ActiveValue::Set(parent_id.map(|id| id as i64)),
+            deleted: ActiveValue::Set(false),
             ..Default::default()
         };
 
-        let raw_comment = new_comment.insert(&self.0).await.map_err(to_db_error)?;
-        let comment = realworld_domain::Comment {
-            id: raw_comment.id as u64,
-            author: user.profile.clone(),
-            body: raw_comment.body,
-            created_at: raw_comment.created_at.into(),
-            updated_at: raw_comment.updated_at.into(),
-        };
-        Ok(comment)
+        let txn = self.0.begin().await.map_err(to_db_error)?;
+        let raw_comment = new_comment.insert(&txn).await.map_err(to_db_error)?;
+        add_to_comments_count(&txn, article.id, 1)
+            .await
+            .map_err(to_db_error)?;
+        txn.commit().await.map_err(to_db_error)?;
+        Ok(to_comment(raw_comment, user.clone()))
     }
 
     async fn get_comment(
         &self,
+        article: &realworld_domain::Article,
         comment_id: u64,
-    ) -> Result<realworld_domain::Comment, realworld_domain::DeleteCommentError> {
-        use crate::entity::comments::Entity as Comments;
+    ) -> Result<realworld_domain::Comment, realworld_domain::ChangeCommentError> {
+        use crate::entity::comments::{self, Entity as Comments};
 
         let comment = Comments::find_by_id(comment_id as i64)
+            .filter(comments::Column::ArticleId.eq(article.id))
             .one(&self.0)
             .await
             .map_err(to_db_error)?
-            .ok_or_else(|| realworld_domain::DeleteCommentError::CommentNotFound {
+            .ok_or_else(|| realworld_domain::ChangeCommentError::CommentNotFound {
                 comment_id,
                 source: anyhow::anyhow!("Comment not found").into(),
             })?;
@@ -270,52 +429,250 @@ impl realworld_domain::repositories::Repository for Repository {
     async fn get_comments(
         &self,
         article: &realworld_domain::Article,
-    ) -> Result<Vec<realworld_domain::Comment>, realworld_domain::DatabaseError> {
+        query: realworld_domain::CommentQuery,
+    ) -> Result<realworld_domain::CommentPage, realworld_domain::DatabaseError> {
         use crate::entity::comments::{self, Entity as Comments};
         use crate::entity::users;
+        use realworld_domain::CommentOrder;
+
+        let mut top_level = Comments::find()
+            .filter(comments::Column::ArticleId.eq(article.id))
+            .filter(comments::Column::ParentId.is_null());
+        let total = top_level
+            .clone()
+            .count(&self.0)
+            .await
+            .map_err(to_db_error)? as u64;
 
-        Ok(Comments::find()
-            .filter(comments::Column::ArticleId.eq(article.slug.clone()))
+        if let Some(cursor) = query.cursor {
+            top_level = match query.order {
+                CommentOrder::Asc => top_level.filter(comments::Column::Id.gt(cursor as i64)),
+                CommentOrder::Desc => top_level.filter(comments::Column::Id.lt(cursor as i64)),
+            };
+        }
+        top_level = match query.order {
+            CommentOrder::Asc => top_level.order_by_asc(comments::Column::Id),
+            CommentOrder::Desc => top_level.order_by_desc(comments::Column::Id),
+        };
+        // One more comment tells whether there is a next page
+        if let Some(limit) = query.limit {
+            top_level = top_level.limit(limit + 1);
+        }
+        let mut top_level: Vec<realworld_domain::Comment> = top_level
+            .offset(query.offset)
             .find_also_related(users::Entity)
             .all(&self.0)
             .await
             .map_err(to_db_error)?
             .into_iter()
             .filter_map(|(comment, user)| user.map(|u| to_comment(comment, u.into())))
-            .collect())
+            .collect();
+        let next_cursor = query.next_cursor(&mut top_level);
+
+        // The replies to the threads of the page, however deep, with the top-level comment
+        // they belong to
+        let ids = json!(top_level.iter().map(|c| c.id).collect::<Vec<_>>());
+        let threads = "WITH RECURSIVE thread(id, root) AS (
+                SELECT r.id, r.parent_id FROM comments r
+                WHERE r.parent_id IN (SELECT jsonb_array_elements_text(?)::bigint)
+                UNION ALL
+                SELECT r.id, t.root FROM comments r JOIN thread t ON r.parent_id = t.id
+            )";
+        let replies_counts = ReplyCount::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                "{} SELECT root, COUNT(*) AS count FROM thread GROUP BY root",
+                threads.replace('?', "$1")
+            ),
+            vec![ids.clone().into()],
+        ))
+        .all(&self.0)
+        .await
+        .map_err(to_db_error)?
+        .into_iter()
+        .map(|c| (c.root as u64, c.count as u64))
+        .collect();
+        let max_replies = query.max_replies.map(|max| max as i64);
+        let replies = Comments::find()
+            .filter(Expr::cust_with_values(
+                &format!(
+                    "comments.id IN ({} SELECT id FROM (
+                        SELECT id, ROW_NUMBER() OVER (PARTITION BY root ORDER BY id) AS n
+                        FROM thread
+                    ) ranked WHERE n <= COALESCE(?, n))",
+                    threads
+                ),
+                [Value::from(ids), Value::from(max_replies)],
+            ))
+            .order_by_asc(comments::Column::Id)
+            .find_also_related(users::Entity)
+            .all(&self.0)
+            .await
+            .map_err(to_db_error)?
+            .into_iter()
+            .filter_map(|(comment, user)| user.map(|u| to_comment(comment, u.into())));
+
+        let mut comments = top_level;
+        comments.extend(replies);
+        Ok(realworld_domain::CommentPage {
+            comments: Page::new(comments, total),
+            next_cursor,
+            replies_counts,
+        })
+    }
+
+    async fn get_comments_views(
+        &self,
+        viewer: &realworld_domain::User,
+        comments: Vec<realworld_domain::Comment>,
+    ) -> Result<Vec<realworld_domain::CommentView>, realworld_domain::DatabaseError> {
+        let authors = comments
+            .iter()
+            .map(|c| c.author.username.as_str())
+            .collect();
+        let followed = self.followed_authors(authors, viewer).await?;
+        let views = comments
+            .into_iter()
+            .map(|comment| {
+                let author = realworld_domain::ProfileView {
+                    following: followed.contains(&comment.author.username),
+                    profile: comment.author.clone(),
+                    viewer: viewer.id,
+                };
+                comment.with_author(author)
+            })
+            .collect();
+        Ok(views)
     }
 
     async fn delete_comment(
         &self,
         comment_id: u64,
-    ) -> Result<(), realworld_domain::DeleteCommentError> {
+    ) -> Result<(), realworld_domain::ChangeCommentError> {
         use crate::entity::comments::Entity as Comments;
-        Comments::delete_by_id(comment_id as i64)
-            .exec(&self.0)
+        let txn = self.0.begin().await.map_err(to_db_error)?;
+        let comment = Comments::find_by_id(comment_id as i64)
+            .one(&txn)
             .await
             .map_err(to_db_error)?;
+        if let Some(comment) = comment {
+            let result = Comments::delete_by_id(comment.id)
+                .exec(&txn)
+                .await
+                .map_err(to_db_error)?;
+            // A concurrent deletion of the same comment may have won the race, and
+            // tombstones are not counted anymore
+            if result.rows_affected > 0 && !comment.deleted {
+                add_to_comments_count(&txn, comment.article_id, -1)
+                    .await
+                    .map_err(to_db_error)?;
+            }
+            if let Some(parent_id) = comment.parent_id {
+                delete_empty_tombstones(&txn, parent_id)
+                    .await
+                    .map_err(to_db_error)?;
+            }
+        }
+        txn.commit().await.map_err(to_db_error)?;
         Ok(())
     }
 
+    async fn has_replies(&self, comment_id: u64) -> Result<bool, realworld_domain::DatabaseError> {
+        use crate::entity::comments::{self, Entity as Comments};
+
+        let n_replies = Comments::find()
+            .filter(comments::Column::ParentId.eq(comment_id as i64))
+            .count(&self.0)
+            .await
+            .map_err(to_db_error)?;
+        Ok(n_replies > 0)
+    }
+
+    async fn tombstone_comment(
+        &self,
+        comment_id: u64,
+    ) -> Result<(), realworld_domain::ChangeCommentError> {
+        use crate::entity::comments::{self, Entity as Comments};
+
+        let txn = self.0.begin().await.map_err(to_db_error)?;
+        let comment = Comments::find_by_id(comment_id as i64)
+            .one(&txn)
+            .await
+            .map_err(to_db_error)?
+            .ok_or_else(|| realworld_domain::ChangeCommentError::CommentNotFound {
+                comment_id,
+                source: anyhow::anyhow!("Comment not found").into(),
+            })?;
+        let result = Comments::update_many()
+            .col_expr(comments::Column::Body, Expr::value(""))
+            .col_expr(comments::Column::Deleted, Expr::value(true))
+            .filter(comments::Column::Id.eq(comment.id))
+            .filter(comments::Column::Deleted.eq(false))
+            .exec(&txn)
+            .await
+            .map_err(to_db_error)?;
+        if result.rows_affected > 0 {
+            add_to_comments_count(&txn, comment.article_id, -1)
+                .await
+                .map_err(to_db_error)?;
+        }
+        txn.commit().await.map_err(to_db_error)?;
+        Ok(())
+    }
+
+    async fn update_comment(
+        &self,
+        comment_id: u64,
+        content: realworld_domain::CommentContent,
+    ) -> Result<realworld_domain::Comment, realworld_domain::ChangeCommentError> {
+        use crate::entity::comments::{ActiveModel, Entity as Comments};
+
+        let comment = Comments::find_by_id(comment_id as i64)
+            .one(&self.0)
+            .await
+            .map_err(to_db_error)?
+            .ok_or_else(|| realworld_domain::ChangeCommentError::CommentNotFound {
+                comment_id,
+                source: anyhow::anyhow!("Comment not found").into(),
+            })?;
+        let mut comment: ActiveModel = comment.into();
+        let now = Utc::now();
+        comment.body = ActiveValue::Set(content.0);
+        comment.updated_at = ActiveValue::Set(now.into());
+        comment.edited_at = ActiveValue::Set(Some(now.into()));
+        let comment = comment.update(&self.0).await.map_err(to_db_error)?;
+
+        let user = self.get_user_by_id(comment.author_id).await?;
+        Ok(to_comment(comment, user))
+    }
+
     async fn update_article(
         &self,
         article: realworld_domain::Article,
         update: realworld_domain::ArticleUpdate,
     ) -> Result<realworld_domain::Article, realworld_domain::DatabaseError> {
+        use crate::entity::article_slug_history::{self, Entity as ArticleSlugHistory};
         use crate::entity::articles::Entity as Article;
         use sea_orm::IntoActiveModel;
-        let slug = article.slug.clone();
 
-        let article = Article::find_by_id(slug.clone())
-            .one(&self.0)
+        let txn = self.0.begin().await.map_err(to_db_error)?;
+        // Concurrent updates of the article wait for this one to commit
+        let model = Article::find_by_id(article.id)
+            .lock_exclusive()
+            .one(&txn)
             .await
             .map_err(to_db_error)?
             .ok_or_else(|| {
                 realworld_domain::DatabaseError::from(anyhow::anyhow!("Article not found"))
             })?;
+        let old_slug = model.slug.clone();
+        let candidates: Box<dyn Iterator<Item = String> + Send> = match update.new_slug(&article) {
+            Some(new_slug) => Box::new(slug_candidates(new_slug)),
+            None => Box::new(std::iter::once(old_slug.clone())),
+        };
 
-        let mut model = article.into_active_model();
-
+        let current_tags = model.tag_list.0.clone();
+        let mut model = model.into_active_model();
         if let Some(title) = update.title {
             model.title = ActiveValue::Set(title);
         }
@@ -325,10 +682,61 @@ impl realworld_domain::repositories::Repository for Repository {
         if let Some(body) = update.body {
             model.body = ActiveValue::Set(body);
         }
+        if let Some(tag_list) = &update.tag_list {
+            let tag_list = tag_list.apply(&current_tags);
+            set_article_tags(&txn, article.id, &tag_list)
+                .await
+                .map_err(to_db_error)?;
+            model.tag_list = ActiveValue::Set(Json(tag_list));
+        }
+        model.updated_at = ActiveValue::Set(Utc::now().into());
+
+        let mut slug = None;
+        for candidate in candidates {
+            if !slug_is_available(&txn, &candidate, Some(&article))
+                .await
+                .map_err(to_db_error)?
+            {
+                continue;
+            }
+            // A concurrent rename may take the slug before this one commits: the savepoint
+            // lets the transaction go on with the next candidate
+            model.slug = ActiveValue::Set(candidate.clone());
+            let savepoint = txn.begin().await.map_err(to_db_error)?;
+            match model.clone().update(&savepoint).await {
+                Ok(_) => {
+                    savepoint.commit().await.map_err(to_db_error)?;
+                    slug = Some(candidate);
+                    break;
+                }
+                Err(e) if violated_unique_constraint(&e) == Some("articles_slug_key") => {
+                    savepoint.rollback().await.map_err(to_db_error)?;
+                }
+                Err(e) => return Err(to_db_error(e)),
+            }
+        }
+        let slug = slug.ok_or_else(|| anyhow::anyhow!("Every slug candidate is taken"))?;
+
+        if slug != old_slug {
+            ArticleSlugHistory::delete_by_id(slug.clone())
+                .exec(&txn)
+                .await
+                .map_err(to_db_error)?;
+            article_slug_history::ActiveModel {
+                slug: ActiveValue::Set(old_slug),
+                created_at: ActiveValue::Set(Utc::now().into()),
+                article_id: ActiveValue::Set(article.id),
+            }
+            .insert(&txn)
+            .await
+            .map_err(to_db_error)?;
+        }
 
-        model.update(&self.0).await.map_err(to_db_error)?;
-
-        let article = self.get_article_by_slug(&slug).await?;
+        let article = find_article_by_slug(&txn, &slug)
+            .await
+            .map_err(to_db_error)?
+            .ok_or_else(|| anyhow::anyhow!("Article not found"))?;
+        txn.commit().await.map_err(to_db_error)?;
 
         Ok(article)
     }
@@ -338,26 +746,22 @@ impl realworld_domain::repositories::Repository for Repository {
         article: &realworld_domain::Article,
         user: &realworld_domain::User,
     ) -> Result<realworld_domain::FavoriteOutcome, realworld_domain::DatabaseError> {
-        use crate::entity::favorites::{self, ActiveModel, Entity as Favorites};
-        let favorite = Favorites::find()
-            .filter(favorites::Column::ArticleId.eq(article.slug.clone()))
-            .filter(favorites::Column::UserId.eq(user.id))
-            .one(&self.0)
+        let txn = self.0.begin().await.map_err(to_db_error)?;
+        let stmt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"INSERT INTO favorites (user_id, article_id) VALUES ($1, $2)
+            ON CONFLICT DO NOTHING"#,
+            vec![user.id.into(), article.id.into()],
+        );
+        let result = txn.execute(stmt).await.map_err(to_db_error)?;
+        if result.rows_affected() == 0 {
+            return Ok(FavoriteOutcome::AlreadyAFavorite);
+        }
+        add_to_favorites_count(&txn, article.id, 1)
             .await
             .map_err(to_db_error)?;
-
-        match favorite {
-            Some(_) => Ok(FavoriteOutcome::AlreadyAFavorite),
-            None => {
-                let model = ActiveModel {
-                    article_id: ActiveValue::Set(article.slug.clone()),
-                    user_id: ActiveValue::Set(user.id),
-                };
-
-                model.insert(&self.0).await.map_err(to_db_error)?;
-                Ok(FavoriteOutcome::NewFavorite)
-            }
-        }
+        txn.commit().await.map_err(to_db_error)?;
+        Ok(FavoriteOutcome::NewFavorite)
     }
 
     async fn unfavorite(
@@ -366,14 +770,19 @@ impl realworld_domain::repositories::Repository for Repository {
         user: &realworld_domain::User,
     ) -> Result<realworld_domain::UnfavoriteOutcome, realworld_domain::DatabaseError> {
         use crate::entity::favorites::{self, Entity as Favorites};
+        let txn = self.0.begin().await.map_err(to_db_error)?;
         let result: DeleteResult = Favorites::delete_many()
-            .filter(favorites::Column::ArticleId.eq(article.slug.clone()))
+            .filter(favorites::Column::ArticleId.eq(article.id))
             .filter(favorites::Column::UserId.eq(user.id))
-            .exec(&self.0)
+            .exec(&txn)
             .await
             .map_err(to_db_error)?;
 
         if result.rows_affected > 0 {
+            add_to_favorites_count(&txn, article.id, -1)
+                .await
+                .map_err(to_db_error)?;
+            txn.commit().await.map_err(to_db_error)?;
             Ok(realworld_domain::UnfavoriteOutcome::WasAFavorite)
         } else {
             Ok(realworld_domain::UnfavoriteOutcome::WasNotAFavorite)
@@ -385,24 +794,37 @@ impl realworld_domain::repositories::Repository for Repository {
         sign_up: realworld_domain::SignUp,
     ) -> Result<realworld_domain::User, realworld_domain::SignUpError> {
         use crate::entity::users;
+        let realworld_domain::SignUp {
+            username,
+            email,
+            password,
+        } = sign_up;
         let user = users::ActiveModel {
             id: ActiveValue::Set(Uuid::new_v4()),
-            username: ActiveValue::Set(sign_up.username),
-            email: ActiveValue::Set(sign_up.email),
-            password: ActiveValue::Set(sign_up.password.hash().to_string()),
+            username: ActiveValue::Set(username.clone()),
+            email: ActiveValue::Set(email.clone()),
+            password: ActiveValue::Set(password.hash().to_string()),
             created_at: ActiveValue::Set(Utc::now().into()),
             updated_at: ActiveValue::Set(Utc::now().into()),
             ..Default::default()
         };
 
+        let txn = self.0.begin().await.map_err(to_db_error)?;
         let user = user
-            .insert(&self.0)
+            .insert(&txn)
+            .await
+            .map_err(|e| match user_conflict(&e) {
+                Some(UserConflict::Username) => {
+                    realworld_domain::SignUpError::UsernameTaken { username }
+                }
+                Some(UserConflict::Email) => realworld_domain::SignUpError::EmailTaken { email },
+                None => to_db_error(e).into(),
+            })?;
+        insert_follower(&txn, user.id, user.id)
             .await
-            .map(Into::into)
             .map_err(to_db_error)?;
-
-        self.follow(&user, &user.profile).await?;
-        Ok(user)
+        txn.commit().await.map_err(to_db_error)?;
+        Ok(user.into())
     }
 
     async fn update_user(
@@ -415,7 +837,7 @@ impl realworld_domain::repositories::Repository for Repository {
             image,
             bio,
         }: realworld_domain::UserUpdate,
-    ) -> Result<realworld_domain::User, realworld_domain::DatabaseError> {
+    ) -> Result<realworld_domain::User, realworld_domain::UpdateUserError> {
         use crate::entity::users::{ActiveModel, Entity as User};
         let mut user: ActiveModel = User::find_by_id(user.id)
             .one(&self.0)
@@ -440,11 +862,20 @@ impl realworld_domain::repositories::Repository for Repository {
         user.bio = ActiveValue::Set(bio);
         user.image = ActiveValue::Set(image);
 
+        let new_username = user.username.clone().unwrap();
+        let new_email = user.email.clone().unwrap();
         user.update(&self.0)
             .await
             .map(Into::into)
-            .map_err(to_db_error)
-            .map(Ok)?
+            .map_err(|e| match user_conflict(&e) {
+                Some(UserConflict::Username) => realworld_domain::UpdateUserError::UsernameTaken {
+                    username: new_username,
+                },
+                Some(UserConflict::Email) => {
+                    realworld_domain::UpdateUserError::EmailTaken { email: new_email }
+                }
+                None => to_db_error(e).into(),
+            })
     }
 
     async fn get_user_by_id(
@@ -476,11 +907,8 @@ impl realworld_domain::repositories::Repository for Repository {
             .one(&self.0)
             .await
             .map_err(to_db_error)?
-            .ok_or(realworld_domain::LoginError::NotFound)?;
-        let stored_password = realworld_domain::Password::from_hash(user.password.to_owned());
-        if !stored_password.verify(password)? {
-            return Err(realworld_domain::LoginError::NotFound);
-        }
+            .ok_or_else(|| realworld_domain::Password::reject_unknown_user(password))?;
+        realworld_domain::Password::from_hash(user.password.to_owned()).check(password)?;
         Ok(user.into())
     }
 
@@ -524,28 +952,10 @@ impl realworld_domain::repositories::Repository for Repository {
         follower: &realworld_domain::User,
         to_be_followed: &realworld_domain::Profile,
     ) -> Result<(), realworld_domain::DatabaseError> {
-        use crate::entity::followers::{self, ActiveModel, Entity as Followers};
-
         let user_to_be_followed = self.get_user_by_username(&to_be_followed.username).await?;
-        let following = Followers::find()
-            .filter(followers::Column::FollowerId.eq(follower.id))
-            .filter(followers::Column::FollowedId.eq(user_to_be_followed.id))
-            .one(&self.0)
+        insert_follower(&self.0, follower.id, user_to_be_followed.id)
             .await
-            .map_err(to_db_error)?;
-
-        match following {
-            Some(_) => Ok(()),
-            None => {
-                let model = ActiveModel {
-                    follower_id: ActiveValue::Set(follower.id),
-                    followed_id: ActiveValue::Set(user_to_be_followed.id),
-                };
-
-                model.insert(&self.0).await.map_err(to_db_error)?;
-                Ok(())
-            }
-        }
+            .map_err(to_db_error)
     }
 
     async fn unfollow(
@@ -570,25 +980,151 @@ impl realworld_domain::repositories::Repository for Repository {
 
     async fn get_tags(
         &self,
-    ) -> Result<std::collections::HashSet<String>, realworld_domain::DatabaseError> {
+        limit: Option<u64>,
+    ) -> Result<Vec<realworld_domain::TagCount>, realworld_domain::DatabaseError> {
         #[derive(Debug, FromQueryResult)]
-        pub struct UniqueTag {
-            tag: String,
+        pub struct TagCount {
+            name: String,
+            count: i64,
         }
+        // `LIMIT NULL` is the same as no limit at all
         let stmt = Statement::from_sql_and_values(
             DbBackend::Postgres,
-            r#"select DISTINCT tag.tag from (select jsonb_array_elements_text(tag_list) as tag from articles) as tag"#,
-            vec![],
+            r#"SELECT tags.name, count(*) AS count FROM tags
+            JOIN article_tags ON article_tags.tag_id = tags.id
+            GROUP BY tags.name ORDER BY count DESC, tags.name LIMIT $1"#,
+            vec![limit.map(|l| l as i64).into()],
         );
-        let tags = UniqueTag::find_by_statement(stmt)
+        let tags = TagCount::find_by_statement(stmt)
             .all(&self.0)
             .await
             .map_err(to_db_error)?
             .into_iter()
-            .map(|tag| tag.tag)
-            .collect::<HashSet<String>>();
+            .map(|t| realworld_domain::TagCount {
+                tag: t.name,
+                count: t.count as u64,
+            })
+            .collect();
         Ok(tags)
     }
+
+    async fn create_session(
+        &self,
+        user: &realworld_domain::User,
+        refresh_token: &realworld_domain::RefreshToken,
+        expires_at: DateTime<Utc>,
+    ) -> Result<realworld_domain::Session, realworld_domain::DatabaseError> {
+        use crate::entity::sessions;
+        let session = sessions::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(user.id),
+            refresh_token_hash: ActiveValue::Set(refresh_token.hash()),
+            created_at: ActiveValue::Set(Utc::now().into()),
+            expires_at: ActiveValue::Set(expires_at.into()),
+            revoked_at: ActiveValue::Set(None),
+        };
+        session
+            .insert(&self.0)
+            .await
+            .map(Into::into)
+            .map_err(to_db_error)
+    }
+
+    async fn get_session(
+        &self,
+        session_id: Uuid,
+    ) -> Result<realworld_domain::Session, realworld_domain::GetSessionError> {
+        use crate::entity::sessions::Entity as Sessions;
+        Sessions::find_by_id(session_id)
+            .one(&self.0)
+            .await
+            .map_err(to_db_error)?
+            .ok_or_else(|| realworld_domain::GetSessionError::NotFound {
+                session_id,
+                source: anyhow::anyhow!("Session not found").into(),
+            })
+            .map(Into::into)
+    }
+
+    async fn get_session_by_refresh_token(
+        &self,
+        refresh_token: &realworld_domain::RefreshToken,
+    ) -> Result<realworld_domain::Session, realworld_domain::GetSessionError> {
+        use crate::entity::sessions::{self, Entity as Sessions};
+        Sessions::find()
+            .filter(sessions::Column::RefreshTokenHash.eq(refresh_token.hash()))
+            .one(&self.0)
+            .await
+            .map_err(to_db_error)?
+            .ok_or_else(
+                || realworld_domain::GetSessionError::NotFoundByRefreshToken {
+                    source: anyhow::anyhow!("Session not found").into(),
+                },
+            )
+            .map(Into::into)
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        session: &realworld_domain::Session,
+        current: &realworld_domain::RefreshToken,
+        refresh_token: &realworld_domain::RefreshToken,
+        expires_at: DateTime<Utc>,
+    ) -> Result<realworld_domain::Session, realworld_domain::RefreshSessionError> {
+        use crate::entity::sessions::{self, Entity as Sessions};
+        // A compare-and-swap on the current token: of two concurrent refreshes, one only wins
+        let rotated = Sessions::update_many()
+            .col_expr(
+                sessions::Column::RefreshTokenHash,
+                Expr::value(refresh_token.hash()),
+            )
+            .col_expr(sessions::Column::ExpiresAt, Expr::value(expires_at))
+            .filter(sessions::Column::Id.eq(session.id))
+            .filter(sessions::Column::RefreshTokenHash.eq(current.hash()))
+            .filter(sessions::Column::RevokedAt.is_null())
+            .exec(&self.0)
+            .await
+            .map_err(to_db_error)?;
+        if rotated.rows_affected == 0 {
+            return Err(realworld_domain::RefreshSessionError::InvalidRefreshToken);
+        }
+        Sessions::find_by_id(session.id)
+            .one(&self.0)
+            .await
+            .map_err(to_db_error)?
+            .map(Into::into)
+            .ok_or(realworld_domain::RefreshSessionError::InvalidRefreshToken)
+    }
+
+    async fn revoke_session(
+        &self,
+        session_id: Uuid,
+    ) -> Result<(), realworld_domain::DatabaseError> {
+        use crate::entity::sessions::{self, Entity as Sessions};
+        Sessions::update_many()
+            .col_expr(sessions::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(sessions::Column::Id.eq(session_id))
+            .filter(sessions::Column::RevokedAt.is_null())
+            .exec(&self.0)
+            .await
+            .map_err(to_db_error)?;
+        Ok(())
+    }
+
+    async fn revoke_sessions(
+        &self,
+        user: &realworld_domain::User,
+    ) -> Result<(), realworld_domain::DatabaseError> {
+        use crate::entity::sessions::{self, Entity as Sessions};
+        Sessions::update_many()
+            .col_expr(sessions::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(sessions::Column::UserId.eq(user.id))
+            .filter(sessions::Column::RevokedAt.is_null())
+            .exec(&self.0)
+            .await
+            .map_err(to_db_error)?;
+        Ok(())
+    }
 }
 
 /// Helper function to cast a diesel::Error into a domain Database Error.
@@ -597,11 +1133,271 @@ pub fn to_db_error(e: sea_orm::DbErr) -> realworld_domain::DatabaseError {
     realworld_domain::DatabaseError::from(anyhow::Error::from(e))
 }
 
+/// The unique column of `users` an insert or an update collided with.
+enum UserConflict {
+    Username,
+    Email,
+}
+
+/// Recognises violations of the unique constraints declared on `users`.
+fn user_conflict(e: &sea_orm::DbErr) -> Option<UserConflict> {
+    match violated_unique_constraint(e)? {
+        "users_username_key" => Some(UserConflict::Username),
+        "users_email_key" => Some(UserConflict::Email),
+        _ => None,
+    }
+}
+
+/// The name of the unique constraint `e` reports a violation of, if any.
+///
+/// SeaORM only hands us the message reported by Postgres, hence the string matching.
+fn violated_unique_constraint(e: &sea_orm::DbErr) -> Option<&str> {
+    let message = match e {
+        sea_orm::DbErr::Exec(message) | sea_orm::DbErr::Query(message) => message,
+        _ => return None,
+    };
+    let (_, constraint) = message.split_once("violates unique constraint \"")?;
+    constraint.split('"').next()
+}
+
+/// Whether an article can use `slug`: slugs of renamed articles stay reserved, except
+/// for `article` going back to one of its old slugs.
+async fn slug_is_available(
+    db: &impl ConnectionTrait,
+    slug: &str,
+    article: Option<&realworld_domain::Article>,
+) -> Result<bool, sea_orm::DbErr> {
+    use crate::entity::article_slug_history::Entity as ArticleSlugHistory;
+    use crate::entity::articles::{self, Entity as Article};
+    let n_articles = Article::find()
+        .filter(articles::Column::Slug.eq(slug))
+        .count(db)
+        .await?;
+    if n_articles > 0 {
+        return Ok(article.is_some_and(|a| a.slug == slug));
+    }
+    let entry = ArticleSlugHistory::find_by_id(slug.to_string())
+        .one(db)
+        .await?;
+    Ok(entry.is_none_or(|e| article.is_some_and(|a| a.id == e.article_id)))
+}
+
+async fn find_article_by_slug(
+    db: &impl ConnectionTrait,
+    slug: &str,
+) -> Result<Option<realworld_domain::Article>, sea_orm::DbErr> {
+    use crate::entity::articles::{self, Entity as Articles};
+    use crate::entity::users;
+    Ok(Articles::find()
+        .filter(articles::Column::Slug.eq(slug))
+        .find_also_related(users::Entity)
+        .one(db)
+        .await?
+        .and_then(|(article, user)| user.map(|u| to_article(article, u.into()))))
+}
+
+/// Does nothing if `follower_id` already follows `followed_id`.
+async fn insert_follower(
+    db: &impl ConnectionTrait,
+    follower_id: Uuid,
+    followed_id: Uuid,
+) -> Result<(), sea_orm::DbErr> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"INSERT INTO followers (follower_id, followed_id) VALUES ($1, $2)
+        ON CONFLICT DO NOTHING"#,
+        vec![follower_id.into(), followed_id.into()],
+    );
+    db.execute(stmt).await?;
+    Ok(())
+}
+
+async fn add_to_favorites_count(
+    db: &impl ConnectionTrait,
+    article_id: Uuid,
+    delta: i64,
+) -> Result<(), sea_orm::DbErr> {
+    use crate::entity::articles::{self, Entity as Articles};
+    Articles::update_many()
+        .col_expr(
+            articles::Column::FavoritesCount,
+            Expr::cust_with_values("GREATEST(favorites_count + ?, 0)", [delta]),
+        )
+        .filter(articles::Column::Id.eq(article_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+async fn add_to_comments_count(
+    db: &impl ConnectionTrait,
+    article_id: Uuid,
+    delta: i64,
+) -> Result<(), sea_orm::DbErr> {
+    use crate::entity::articles::{self, Entity as Articles};
+    Articles::update_many()
+        .col_expr(
+            articles::Column::CommentsCount,
+            Expr::col(articles::Column::CommentsCount).add(delta),
+        )
+        .filter(articles::Column::Id.eq(article_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Makes `tags` the tags of the article, creating the ones that do not exist yet.
+///
+/// `articles.tag_list` holds the tags articles are read with, and `tags` and `article_tags`
+/// index them for filtering and counting: call this in the transaction setting `tag_list`.
+async fn set_article_tags(
+    db: &impl ConnectionTrait,
+    article_id: Uuid,
+    tags: &[String],
+) -> Result<(), sea_orm::DbErr> {
+    use crate::entity::article_tags::{self, Entity as ArticleTags};
+    ArticleTags::delete_many()
+        .filter(article_tags::Column::ArticleId.eq(article_id))
+        .exec(db)
+        .await?;
+    if tags.is_empty() {
+        return Ok(());
+    }
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"INSERT INTO tags (name) SELECT jsonb_array_elements_text($1)
+        ON CONFLICT (name) DO NOTHING"#,
+        vec![json!(tags).into()],
+    ))
+    .await?;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"INSERT INTO article_tags (article_id, tag_id)
+        SELECT $1, id FROM tags WHERE name IN (SELECT jsonb_array_elements_text($2))"#,
+        vec![article_id.into(), json!(tags).into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Deletes the tombstone `comment_id` if it has no replies left, then its parent likewise.
+async fn delete_empty_tombstones(
+    db: &impl ConnectionTrait,
+    mut comment_id: i64,
+) -> Result<(), sea_orm::DbErr> {
+    use crate::entity::comments::{self, Entity as Comments};
+    loop {
+        // Concurrent deletions of its last replies wait for each other on the parent, so
+        // that the last one sees no reply left
+        let parent = Comments::find_by_id(comment_id)
+            .lock_exclusive()
+            .one(db)
+            .await?;
+        let parent = match parent {
+            Some(parent) if parent.deleted => parent,
+            _ => return Ok(()),
+        };
+        let n_replies = Comments::find()
+            .filter(comments::Column::ParentId.eq(comment_id))
+            .count(db)
+            .await?;
+        if n_replies > 0 {
+            return Ok(());
+        }
+        Comments::delete_by_id(comment_id).exec(db).await?;
+        match parent.parent_id {
+            Some(parent_id) => comment_id = parent_id,
+            None => return Ok(()),
+        }
+    }
+}
+
+#[derive(FromQueryResult)]
+struct ReplyCount {
+    root: i64,
+    count: i64,
+}
+
 #[derive(FromQueryResult)]
 struct Count {
     count: i64,
 }
 impl Repository {
+    /// Articles matching the filters of `query`, joined with their author.
+    ///
+    /// `None` if no article can match, e.g. when filtering by the favorites of a missing user.
+    async fn filter_articles(
+        &self,
+        query: &realworld_domain::ArticleQuery,
+    ) -> Result<
+        Option<SelectTwo<crate::entity::articles::Entity, crate::entity::users::Entity>>,
+        realworld_domain::DatabaseError,
+    > {
+        use crate::entity::articles::Entity as Article;
+        use crate::entity::favorites;
+        use crate::entity::users;
+        use sea_orm::RelationTrait;
+
+        let mut q = Article::find().find_also_related(users::Entity);
+
+        if let Some(username) = &query.author {
+            q = q.filter(users::Column::Username.eq(username.as_str()));
+        }
+
+        if let Some(username) = &query.favorited {
+            let fan = users::Entity::find()
+                .filter(users::Column::Username.eq(username.as_str()))
+                .one(&self.0)
+                .await
+                .map_err(to_db_error)?;
+            let fan = match fan {
+                Some(fan) => fan,
+                None => return Ok(None),
+            };
+            q = q
+                .join_rev(JoinType::InnerJoin, favorites::Relation::Articles.def())
+                .filter(favorites::Column::UserId.eq(fan.id));
+        }
+
+        let tags = query.tag_filter();
+        if !tags.is_empty() {
+            let tagged = "SELECT article_tags.article_id FROM article_tags \
+                JOIN tags ON tags.id = article_tags.tag_id \
+                WHERE tags.name IN (SELECT jsonb_array_elements_text(?))";
+            q = q.filter(match query.tag_match {
+                TagMatch::Any => {
+                    Expr::cust_with_values(&format!("articles.id IN ({})", tagged), [json!(tags)])
+                }
+                // Articles having as many of the requested tags as there are tags
+                TagMatch::All => Expr::cust_with_values(
+                    &format!(
+                        "articles.id IN ({} GROUP BY article_tags.article_id HAVING count(*) = ?)",
+                        tagged
+                    ),
+                    [Value::from(json!(tags)), Value::from(tags.len() as i64)],
+                ),
+            });
+        }
+
+        Ok(Some(q))
+    }
+
+    /// Recounts the favorites of every article from the `favorites` table, in case the
+    /// `favorites_count` column went out of sync. Returns the number of articles fixed.
+    pub async fn recount_favorites(&self) -> Result<u64, realworld_domain::DatabaseError> {
+        let stmt = Statement::from_string(
+            DbBackend::Postgres,
+            r#"UPDATE articles SET favorites_count = counts.count
+            FROM (SELECT articles.id, count(favorites.article_id) AS count FROM articles
+                LEFT JOIN favorites ON favorites.article_id = articles.id
+                GROUP BY articles.id) AS counts
+            WHERE counts.id = articles.id AND articles.favorites_count <> counts.count"#
+                .to_string(),
+        );
+        let result = self.0.execute(stmt).await.map_err(to_db_error)?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn n_favorites(
         &self,
         article: &Article,
@@ -610,7 +1406,7 @@ impl Repository {
         Favorites::find()
             .select_only()
             .column_as(Expr::cust("count(*)"), "count")
-            .filter(favorites::Column::ArticleId.eq(article.slug.clone()))
+            .filter(favorites::Column::ArticleId.eq(article.id))
             .into_model::<Count>()
             .one(&self.0)
             .await
@@ -624,18 +1420,18 @@ impl Repository {
     ) -> Result<bool, realworld_domain::DatabaseError> {
         use crate::entity::favorites::{self, Entity as Favorites};
         Favorites::find()
-            .filter(favorites::Column::ArticleId.eq(article.slug.clone()))
+            .filter(favorites::Column::ArticleId.eq(article.id))
             .filter(favorites::Column::UserId.eq(user.id))
             .one(&self.0)
             .await
             .map(|row| row.is_some())
             .map_err(to_db_error)
     }
-    pub async fn are_favorites<'a>(
+    pub async fn are_favorites(
         &self,
-        articles: &'a [realworld_domain::Article],
+        articles: &[realworld_domain::Article],
         user: &realworld_domain::User,
-    ) -> Result<HashMap<&'a str, bool>, realworld_domain::DatabaseError> {
+    ) -> Result<HashMap<Uuid, bool>, realworld_domain::DatabaseError> {
         use crate::entity::favorites::{self, Entity as Favorites};
 
         let favs = Favorites::find()
@@ -643,8 +1439,8 @@ impl Repository {
                 favorites::Column::ArticleId.is_in(
                     articles
                         .iter()
-                        .map(|article| article.slug.as_str())
-                        .collect::<Vec<&str>>(),
+                        .map(|article| article.id)
+                        .collect::<Vec<_>>(),
                 ),
             )
             .filter(favorites::Column::UserId.eq(user.id))
@@ -652,21 +1448,46 @@ impl Repository {
             .await
             .map_err(to_db_error)?;
 
-        let favs_slugs = favs
+        let favs_ids = favs
             .iter()
-            .map(|favorite| favorite.article_id.as_str())
-            .collect::<HashSet<&str>>();
+            .map(|favorite| favorite.article_id)
+            .collect::<HashSet<Uuid>>();
 
         let mut favorited = HashMap::new();
         for article in articles.iter() {
-            favorited.insert(
-                article.slug.as_str(),
-                favs_slugs.contains(article.slug.as_str()),
-            );
+            favorited.insert(article.id, favs_ids.contains(&article.id));
         }
 
         Ok(favorited)
     }
+    /// The usernames among `authors` of the users `viewer` follows, with a single query.
+    async fn followed_authors(
+        &self,
+        authors: HashSet<&str>,
+        viewer: &realworld_domain::User,
+    ) -> Result<HashSet<String>, realworld_domain::DatabaseError> {
+        use crate::entity::followers::{self, Entity as Followers};
+        use crate::entity::users;
+        use sea_orm::RelationTrait;
+        #[derive(FromQueryResult)]
+        struct Followed {
+            username: String,
+        }
+        if authors.is_empty() {
+            return Ok(HashSet::new());
+        }
+        Followers::find()
+            .select_only()
+            .column(users::Column::Username)
+            .join(JoinType::InnerJoin, followers::Relation::Users2.def())
+            .filter(followers::Column::FollowerId.eq(viewer.id))
+            .filter(users::Column::Username.is_in(authors))
+            .into_model::<Followed>()
+            .all(&self.0)
+            .await
+            .map(|rows| rows.into_iter().map(|f| f.username).collect())
+            .map_err(to_db_error)
+    }
     pub async fn is_following(
         &self,
         viewer: &realworld_domain::User,
@@ -700,3 +1521,18 @@ impl Repository {
             .map(Into::into)
     }
 }
+
+/// SQL escaping the HTML special characters of the text `expr` evaluates to.
+fn escape_html(expr: &str) -> String {
+    [
+        ("&", "&amp;"),
+        ("<", "&lt;"),
+        (">", "&gt;"),
+        ("\"", "&quot;"),
+        ("''", "&#39;"),
+    ]
+    .iter()
+    .fold(expr.to_string(), |expr, (c, entity)| {
+        format!("replace({}, '{}', '{}')", expr, c, entity)
+    })
+}
@@ -17,7 +17,6 @@ impl From<crate::entity::users::Model> for User {
 pub fn to_article(
     a: crate::entity::articles::Model,
     u: realworld_domain::User,
-    n_fav: u64,
 ) -> realworld_domain::Article {
     let metadata = realworld_domain::ArticleMetadata {
         created_at: a.created_at.into(),
@@ -32,11 +31,12 @@ pub fn to_article(
         tag_list: tags,
     };
     realworld_domain::Article {
+        id: a.id,
         content,
         slug: a.slug,
         author: u.profile,
         metadata,
-        favorites_count: n_fav,
+        favorites_count: a.favorites_count as u64,
     }
 }
 impl From<crate::entity::users::Model> for Profile {
@@ -51,9 +51,25 @@ impl From<crate::entity::users::Model> for Profile {
 pub fn to_comment(c: crate::entity::comments::Model, u: User) -> realworld_domain::Comment {
     realworld_domain::Comment {
         id: c.id as u64,
+        article_id: c.article_id,
+        parent_id: c.parent_id.map(|id| id as u64),
         author: u.profile,
         body: c.body,
         created_at: c.created_at.into(),
         updated_at: c.updated_at.into(),
+        edited_at: c.edited_at.map(Into::into),
+        deleted: c.deleted,
+    }
+}
+
+impl From<crate::entity::sessions::Model> for realworld_domain::Session {
+    fn from(s: crate::entity::sessions::Model) -> Self {
+        realworld_domain::Session {
+            id: s.id,
+            user_id: s.user_id,
+            created_at: s.created_at.into(),
+            expires_at: s.expires_at.into(),
+            revoked_at: s.revoked_at.map(Into::into),
+        }
     }
 }
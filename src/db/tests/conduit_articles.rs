@@ -1,7 +1,16 @@
-use crate::helpers::{create_articles, create_users};
-use realworld_domain::{repositories::Repository, User};
+use crate::helpers::{create_article, create_articles, create_users};
+use realworld_domain::{
+    repositories::Repository, Article, ArticleQuery, ArticleUpdate, CommentContent, CommentQuery,
+    FeedQuery, TagListUpdate, User,
+};
 use realworld_tests::db::test_db;
-use sea_orm::EntityTrait;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::instrument::WithSubscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
 mod helpers;
 #[tokio::test]
 async fn list_articles() {
@@ -42,8 +51,219 @@ async fn delete_article() {
         .expect("Failed to delete article");
 
     let results = db.0.find_articles(Default::default()).await.unwrap();
-    assert_eq!(results.len() as i32, n_articles - 1);
+    assert_eq!(results.items.len() as i32, n_articles - 1);
 
     let result = db.0.get_article_by_slug(&slug).await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn concurrent_renames_to_the_same_title_get_distinct_slugs() {
+    let db = test_db("concurrent_renames_to_the_same_title_get_distinct_slugs").await;
+    let users: Vec<User> = create_users(&db, 8)
+        .await
+        .into_iter()
+        .map(|(u, _)| u)
+        .collect();
+    let articles = create_articles(&db, users).await;
+
+    let renames = articles.into_iter().map(|article| {
+        let repository = db.0.clone();
+        tokio::spawn(async move {
+            let update = ArticleUpdate {
+                title: Some("The same title".to_string()),
+                description: None,
+                body: None,
+                tag_list: None,
+                slug_follows_title: true,
+            };
+            repository.update_article(article, update).await
+        })
+    });
+    let mut slugs = vec![];
+    for rename in renames.collect::<Vec<_>>() {
+        slugs.push(rename.await.unwrap().unwrap().slug);
+    }
+    assert!(slugs.iter().all(|s| s.starts_with("the-same-title")));
+    slugs.sort();
+    slugs.dedup();
+    assert_eq!(slugs.len(), 8);
+}
+
+/// The tags `article_tags` links the article to.
+async fn indexed_tags(db: &realworld_tests::db::Db, article: &Article) -> Vec<String> {
+    use realworld_db::entity::{article_tags, tags};
+    let rows = article_tags::Entity::find()
+        .filter(article_tags::Column::ArticleId.eq(article.id))
+        .find_also_related(tags::Entity)
+        .all(&db.0.pool())
+        .await
+        .unwrap();
+    let mut names: Vec<String> = rows.into_iter().map(|(_, t)| t.unwrap().name).collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn indexed_tags_follow_the_tag_list() {
+    let db = test_db("indexed_tags_follow_the_tag_list").await;
+    let author = create_users(&db, 1).await.remove(0).0;
+    let article = create_article(&db, &author).await;
+    assert_eq!(indexed_tags(&db, &article).await, article.content.tag_list);
+
+    let update = |tag_list| ArticleUpdate {
+        title: Some("Renamed".to_string()),
+        description: None,
+        body: None,
+        tag_list,
+        slug_follows_title: true,
+    };
+    let updates = [
+        Some(TagListUpdate {
+            replace: Some(vec!["dragons".to_string(), "vikings".to_string()]),
+            add: vec![],
+            remove: vec![],
+        }),
+        Some(TagListUpdate {
+            replace: None,
+            add: vec!["training".to_string()],
+            remove: vec!["vikings".to_string()],
+        }),
+        None,
+        Some(TagListUpdate {
+            replace: Some(vec![]),
+            add: vec![],
+            remove: vec![],
+        }),
+    ];
+    for tag_list in updates {
+        let updated =
+            db.0.update_article(article.clone(), update(tag_list))
+                .await
+                .unwrap();
+        let article = db.0.get_article_by_slug(&updated.slug).await.unwrap();
+        assert_eq!(article.content.tag_list, updated.content.tag_list);
+        assert_eq!(indexed_tags(&db, &article).await, article.content.tag_list);
+    }
+}
+
+/// Counts the statements SeaORM sends to the database.
+#[derive(Clone, Default)]
+struct QueryCounter(Arc<AtomicUsize>);
+
+impl<S: tracing::Subscriber> Layer<S> for QueryCounter {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        // Statements are logged right before being executed, outside or inside a transaction
+        let target = event.metadata().target();
+        if target == "sea_orm::driver::sqlx_postgres" || target == "sea_orm::database::transaction"
+        {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+async fn count_queries<T>(f: impl Future<Output = T>) -> (T, usize) {
+    let counter = QueryCounter::default();
+    let subscriber = tracing_subscriber::registry().with(counter.clone());
+    let result = f.with_subscriber(subscriber).await;
+    (result, counter.0.load(Ordering::SeqCst))
+}
+
+#[tokio::test]
+async fn listing_articles_takes_a_fixed_number_of_queries() {
+    let db = test_db("listing_articles_takes_a_fixed_number_of_queries").await;
+    let users: Vec<User> = create_users(&db, 4)
+        .await
+        .into_iter()
+        .map(|(u, _)| u)
+        .collect();
+    let viewer = &users[0];
+    for author in &users[1..] {
+        db.0.follow(viewer, &author.profile).await.unwrap();
+        for _ in 0..5 {
+            let article = create_article(&db, author).await;
+            db.0.favorite(&article, viewer).await.unwrap();
+        }
+    }
+
+    let list = |limit| {
+        let repository = &db.0;
+        async move {
+            let query = ArticleQuery {
+                limit: Some(limit),
+                ..Default::default()
+            };
+            let page = repository.find_articles(query).await.unwrap();
+            let views = repository
+                .get_articles_views(viewer, page.items)
+                .await
+                .unwrap();
+            assert_eq!(views.len() as u64, limit);
+            assert!(views
+                .iter()
+                .all(|v| v.favorited && v.favorites_count == 1 && v.author.following));
+        }
+    };
+    // Counting, listing, favorited flags and followed authors
+    assert_eq!(count_queries(list(1)).await.1, 4);
+    assert_eq!(count_queries(list(15)).await.1, 4);
+
+    let feed = |limit| {
+        let repository = &db.0;
+        async move {
+            let query = FeedQuery {
+                limit,
+                offset: 0,
+                cursor: None,
+            };
+            let page = repository.feed(viewer, query).await.unwrap();
+            assert_eq!(page.articles.items.len() as u64, limit);
+        }
+    };
+    assert_eq!(count_queries(feed(1)).await.1, 4);
+    assert_eq!(count_queries(feed(15)).await.1, 4);
+}
+
+#[tokio::test]
+async fn listing_comments_takes_a_fixed_number_of_queries() {
+    let db = test_db("listing_comments_takes_a_fixed_number_of_queries").await;
+    let users: Vec<User> = create_users(&db, 4)
+        .await
+        .into_iter()
+        .map(|(u, _)| u)
+        .collect();
+    let viewer = &users[0];
+    let article = create_article(&db, viewer).await;
+    for author in &users[1..] {
+        db.0.follow(viewer, &author.profile).await.unwrap();
+        for _ in 0..5 {
+            let content = CommentContent("A comment".to_string());
+            let comment = db.0.comment_article(author, &article, content, None);
+            let comment = comment.await.unwrap();
+            let reply = CommentContent("A reply".to_string());
+            db.0.comment_article(viewer, &article, reply, Some(comment.id))
+                .await
+                .unwrap();
+        }
+    }
+
+    let list = |limit| {
+        let (repository, article) = (&db.0, &article);
+        async move {
+            let query = CommentQuery {
+                limit: Some(limit),
+                ..Default::default()
+            };
+            let page = repository.get_comments(article, query).await.unwrap();
+            let views = repository
+                .get_comments_views(viewer, page.comments.items)
+                .await
+                .unwrap();
+            assert_eq!(views.len() as u64, 2 * limit);
+            assert!(views.iter().all(|v| v.author.following));
+        }
+    };
+    // Counting, listing threads, counting and listing replies, and followed authors
+    assert_eq!(count_queries(list(1)).await.1, 5);
+    assert_eq!(count_queries(list(15)).await.1, 5);
+}
@@ -0,0 +1,9 @@
+use realworld_db::Repository;
+use realworld_tests::db::test_db;
+use realworld_tests::repository_conformance;
+
+async fn repository(name: &str) -> Repository {
+    test_db(name).await.0
+}
+
+repository_conformance!(repository);
@@ -7,6 +7,7 @@ use realworld_domain::{Article, ArticleMetadata, Profile};
 
 use crate::helpers::generate::article_content;
 use realworld_tests::db::test_db;
+use uuid::Uuid;
 
 #[tokio::test]
 async fn you_cannot_favorite_an_article_which_does_not_exist() {
@@ -18,6 +19,7 @@ async fn you_cannot_favorite_an_article_which_does_not_exist() {
     let result =
         db.0.favorite(
             &Article {
+                id: Uuid::new_v4(),
                 slug: article_slug.to_string(),
                 content: article_content(),
                 author: Profile {
@@ -76,20 +78,81 @@ async fn favorites_works() {
     let fans = create_users(&db, n_fans).await;
 
     for (fan, _) in &fans {
-        assert!(!db.0.is_favorite(&article, &fan).await.unwrap());
-        db.0.favorite(&article, &fan)
+        assert!(!db.0.is_favorite(&article, fan).await.unwrap());
+        db.0.favorite(&article, fan)
             .await
             .expect("Failed to fav article");
-        assert!(db.0.is_favorite(&article, &fan).await.unwrap());
+        assert!(db.0.is_favorite(&article, fan).await.unwrap());
     }
 
     assert_eq!(n_fans as i64, db.0.n_favorites(&article).await.unwrap());
 
     for (fan, _) in &fans {
-        db.0.unfavorite(&article, &fan)
+        db.0.unfavorite(&article, fan)
             .await
             .expect("Failed to fav article");
     }
 
     assert_eq!(0, db.0.n_favorites(&article).await.unwrap());
 }
+
+#[tokio::test]
+async fn favorites_counts_can_be_repaired() {
+    use realworld_db::entity::articles::{self, Entity as Articles};
+    use sea_orm::sea_query::Expr;
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+    let db = test_db("favorites_counts_can_be_repaired").await;
+    let author = create_user(&db).await.0;
+    let article = create_article(&db, &author).await;
+    let other = create_article(&db, &author).await;
+    db.0.favorite(&article, &author).await.unwrap();
+    assert_eq!(db.0.recount_favorites().await.unwrap(), 0);
+
+    Articles::update_many()
+        .col_expr(articles::Column::FavoritesCount, Expr::value(42))
+        .filter(articles::Column::Id.is_in([article.id, other.id]))
+        .exec(&db.0.pool())
+        .await
+        .unwrap();
+    assert_eq!(db.0.recount_favorites().await.unwrap(), 2);
+
+    let count = |slug: String| {
+        let repository = db.0.clone();
+        async move {
+            repository
+                .get_article_by_slug(&slug)
+                .await
+                .unwrap()
+                .favorites_count
+        }
+    };
+    assert_eq!(count(article.slug).await, 1);
+    assert_eq!(count(other.slug).await, 0);
+}
+
+#[tokio::test]
+async fn drifted_favorites_counts_do_not_go_negative() {
+    use realworld_db::entity::articles::{self, Entity as Articles};
+    use sea_orm::sea_query::Expr;
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+    let db = test_db("drifted_favorites_counts_do_not_go_negative").await;
+    let author = create_user(&db).await.0;
+    let article = create_article(&db, &author).await;
+    author.favorite(article.clone(), &db.0).await.unwrap();
+
+    Articles::update_many()
+        .col_expr(articles::Column::FavoritesCount, Expr::value(0))
+        .filter(articles::Column::Id.eq(article.id))
+        .exec(&db.0.pool())
+        .await
+        .unwrap();
+    let drifted = db.0.get_article_by_slug(&article.slug).await.unwrap();
+    assert_eq!(drifted.favorites_count, 0);
+
+    let view = author.unfavorite(drifted, &db.0).await.unwrap();
+    assert_eq!(view.favorites_count, 0);
+    let article = db.0.get_article_by_slug(&article.slug).await.unwrap();
+    assert_eq!(article.favorites_count, 0);
+}
@@ -27,7 +27,7 @@ pub async fn create_user(db: &Db) -> (User, String) {
 pub async fn create_articles(repo: &Db, users: Vec<User>) -> Vec<Article> {
     let articles = users
         .iter()
-        .map(|user| create_article(repo, &user).boxed())
+        .map(|user| create_article(repo, user).boxed())
         .collect::<Vec<_>>();
 
     join_all(articles).await
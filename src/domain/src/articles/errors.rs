@@ -1,4 +1,4 @@
-use crate::{DatabaseError, GetUserError};
+use crate::{DatabaseError, GetUserError, ValidationError};
 use uuid::Uuid;
 
 #[derive(thiserror::Error, Debug)]
@@ -29,6 +29,8 @@ pub enum ChangeArticleError {
     },
     #[error("User {user_id:?} is not the author of the article (slug: {slug:?}).")]
     Forbidden { user_id: Uuid, slug: String },
+    #[error(transparent)]
+    Invalid(#[from] ValidationError),
     #[error("Something went wrong.")]
     DatabaseError(#[from] DatabaseError),
 }
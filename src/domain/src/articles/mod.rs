@@ -1,5 +1,7 @@
 pub mod errors;
 pub mod models;
+pub mod slug;
 
 pub use errors::*;
 pub use models::*;
+pub use slug::*;
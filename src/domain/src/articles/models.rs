@@ -1,7 +1,10 @@
+use crate::articles::slug::{slug_candidates, slugify};
 use crate::repositories::Repository;
-use crate::{Comment, DatabaseError, Profile, ProfileView};
+use crate::validation::{
+    MAX_BODY_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_SEARCH_LENGTH, MAX_TITLE_LENGTH,
+};
+use crate::{CommentPage, CommentQuery, DatabaseError, Profile, ProfileView, ValidationError};
 use chrono::{DateTime, Utc};
-use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -14,17 +17,40 @@ pub struct ArticleContent {
 }
 
 impl ArticleContent {
+    /// Fails if any field is blank or too long, or if the tags are not acceptable.
+    pub fn new(
+        title: String,
+        description: String,
+        body: String,
+        tag_list: Vec<String>,
+    ) -> Result<Self, ValidationError> {
+        let mut errors = ValidationError::default();
+        check_title(&mut errors, &title);
+        check_description(&mut errors, &description);
+        check_body(&mut errors, &body);
+        errors.check_tags(&tag_list);
+        errors.or_ok(ArticleContent {
+            title,
+            description,
+            body,
+            tag_list: normalize_tags(tag_list),
+        })
+    }
+
     /// Convert a title into a url-safe slug
     pub fn slug(&self) -> String {
-        self.title
-            .to_ascii_lowercase()
-            .split_ascii_whitespace()
-            .join("-")
+        slugify(&self.title)
+    }
+
+    /// The slugs to try, in order, until one is not taken yet.
+    pub fn slug_candidates(&self) -> impl Iterator<Item = String> {
+        slug_candidates(self.slug())
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Article {
+    pub id: Uuid,
     pub content: ArticleContent,
     pub slug: String,
     pub author: Profile,
@@ -35,9 +61,10 @@ pub struct Article {
 impl Article {
     pub async fn comments(
         &self,
+        query: CommentQuery,
         repository: &impl Repository,
-    ) -> Result<Vec<Comment>, DatabaseError> {
-        repository.get_comments(self).await
+    ) -> Result<CommentPage, DatabaseError> {
+        repository.get_comments(self, query).await
     }
 }
 
@@ -49,6 +76,7 @@ pub struct ArticleMetadata {
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ArticleView {
+    pub id: Uuid,
     pub content: ArticleContent,
     pub slug: String,
     pub author: ProfileView,
@@ -64,11 +92,180 @@ pub struct ArticleUpdate {
     pub title: Option<String>,
     pub description: Option<String>,
     pub body: Option<String>,
+    pub tag_list: Option<TagListUpdate>,
+    /// Whether the slug is regenerated when the title changes.
+    pub slug_follows_title: bool,
+}
+
+impl ArticleUpdate {
+    /// Applies the same checks as [`ArticleContent::new`] to the fields being changed.
+    pub fn new(
+        title: Option<String>,
+        description: Option<String>,
+        body: Option<String>,
+        tag_list: Option<TagListUpdate>,
+    ) -> Result<Self, ValidationError> {
+        let mut errors = ValidationError::default();
+        if let Some(title) = &title {
+            check_title(&mut errors, title);
+        }
+        if let Some(description) = &description {
+            check_description(&mut errors, description);
+        }
+        if let Some(body) = &body {
+            check_body(&mut errors, body);
+        }
+        if let Some(tag_list) = &tag_list {
+            errors.check_tags(tag_list.replace.as_deref().unwrap_or_default());
+            errors.check_tags(&tag_list.add);
+        }
+        errors.or_ok(ArticleUpdate {
+            title,
+            description,
+            body,
+            tag_list,
+            slug_follows_title: false,
+        })
+    }
+
+    /// The slug `article` should move to, before handling collisions.
+    ///
+    /// `None` if the slug stays as it is: titles differing only in case or punctuation
+    /// keep the same slug.
+    pub fn new_slug(&self, article: &Article) -> Option<String> {
+        if !self.slug_follows_title {
+            return None;
+        }
+        let slug = slugify(self.title.as_ref()?);
+        (slug != slugify(&article.content.title)).then_some(slug)
+    }
+}
+
+/// Changes to the tags of an article.
+///
+/// `replace`, if any, is applied first, then tags are added and finally removed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TagListUpdate {
+    pub replace: Option<Vec<String>>,
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+impl TagListUpdate {
+    /// The tags an article tagged with `current` ends up with, normalized.
+    pub fn apply(&self, current: &[String]) -> Vec<String> {
+        let remove = normalize_tags(self.remove.iter().cloned());
+        let tags = self.replace.as_deref().unwrap_or(current).iter();
+        normalize_tags(tags.chain(&self.add).cloned())
+            .into_iter()
+            .filter(|tag| !remove.contains(tag))
+            .collect()
+    }
+}
+
+/// Trims and lowercases tags, dropping the blank ones and the duplicates.
+///
+/// The order in which tags first appear is preserved.
+pub fn normalize_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// What users look for in the title, description and body of articles.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchText(pub String);
+
+impl SearchText {
+    /// Fails if the text is blank or too long.
+    pub fn new(text: String) -> Result<Self, ValidationError> {
+        let mut errors = ValidationError::default();
+        errors.check_not_blank("q", &text);
+        errors.check_max_length("q", &text, MAX_SEARCH_LENGTH);
+        errors.or_ok(SearchText(text))
+    }
+}
+
+/// An article matching a [`SearchText`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchHit {
+    pub article: Article,
+    /// An HTML-escaped excerpt of the article, with the matching words wrapped in `<mark>` tags.
+    pub snippet: String,
+}
+
+/// A tag and the number of articles using it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: u64,
+}
+
+fn check_title(errors: &mut ValidationError, title: &str) {
+    errors.check_not_blank("title", title);
+    errors.check_max_length("title", title, MAX_TITLE_LENGTH);
+}
+
+fn check_description(errors: &mut ValidationError, description: &str) {
+    errors.check_not_blank("description", description);
+    errors.check_max_length("description", description, MAX_DESCRIPTION_LENGTH);
+}
+
+fn check_body(errors: &mut ValidationError, body: &str) {
+    errors.check_not_blank("body", body);
+    errors.check_max_length("body", body, MAX_BODY_LENGTH);
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ArticleQuery {
     pub author: Option<String>,
     pub favorited: Option<String>,
-    pub tag: Option<String>,
+    /// Tags to filter by: no filtering happens if empty.
+    pub tags: Vec<String>,
+    /// Whether articles must have any or all of `tags`.
+    pub tag_match: TagMatch,
+    pub sort: ArticleSort,
+    /// Maximum number of articles to return: all matching articles are returned if `None`.
+    pub limit: Option<u64>,
+    /// Number of matching articles to skip.
+    pub offset: Option<u64>,
+}
+
+impl ArticleQuery {
+    /// The tags to filter by, normalized like those stored with articles.
+    pub fn tag_filter(&self) -> Vec<String> {
+        normalize_tags(self.tags.iter().cloned())
+    }
+}
+
+/// The order articles are listed in.
+///
+/// Ties are broken by recency, the most recent first, except for [`ArticleSort::Oldest`].
+#[derive(Default, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArticleSort {
+    /// The most recently created first.
+    #[default]
+    Recent,
+    /// The least recently created first.
+    Oldest,
+    MostFavorited,
+    MostCommented,
+    /// The most recently updated first.
+    Updated,
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    /// Articles having at least one of the requested tags.
+    #[default]
+    Any,
+    /// Articles having every requested tag.
+    All,
 }
@@ -0,0 +1,66 @@
+//! Turning article titles into url-safe, unique slugs.
+use deunicode::deunicode;
+use uuid::Uuid;
+
+/// Leaves room for a suffix within the 255 characters of `articles.slug`.
+pub const MAX_SLUG_LENGTH: usize = 200;
+/// Suffixes `-2`, `-3`, ... tried before falling back to random ones.
+const NUMERIC_SUFFIXES: u32 = 4;
+/// Short random suffixes tried before falling back to whole UUIDs.
+const RANDOM_SUFFIXES: u32 = 5;
+
+/// Transliterates `title` to ASCII, then keeps lowercase letters and digits,
+/// joined by single dashes.
+pub fn slugify(title: &str) -> String {
+    let ascii = deunicode(title).to_ascii_lowercase();
+    let mut slug = String::with_capacity(ascii.len());
+    for word in ascii
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        if slug.len() + word.len() + 1 > MAX_SLUG_LENGTH {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(word);
+    }
+    if slug.is_empty() {
+        // Titles made of punctuation only, or a single word longer than the limit
+        slug = ascii
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .take(MAX_SLUG_LENGTH)
+            .collect();
+    }
+    if slug.is_empty() {
+        slug.push_str("article");
+    }
+    slug
+}
+
+/// The slugs to try, in order, when `slug` is already taken: `slug` itself, a few numeric
+/// suffixes, a few short random ones and then as many UUIDs as needed, so that they never
+/// run out.
+pub fn slug_candidates(slug: String) -> impl Iterator<Item = String> + Send {
+    let numeric = {
+        let slug = slug.clone();
+        (2..NUMERIC_SUFFIXES + 2).map(move |n| format!("{}-{}", slug, n))
+    };
+    let random = {
+        let slug = slug.clone();
+        (0..RANDOM_SUFFIXES).map(move |_| {
+            let suffix = Uuid::new_v4().to_simple().to_string();
+            format!("{}-{}", slug, &suffix[..8])
+        })
+    };
+    let uuids = {
+        let slug = slug.clone();
+        std::iter::repeat_with(move || format!("{}-{}", slug, Uuid::new_v4().to_simple()))
+    };
+    std::iter::once(slug)
+        .chain(numeric)
+        .chain(random)
+        .chain(uuids)
+}
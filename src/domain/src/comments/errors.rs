@@ -2,7 +2,7 @@ use crate::{DatabaseError, GetUserError};
 use uuid::Uuid;
 
 #[derive(thiserror::Error, Debug)]
-pub enum DeleteCommentError {
+pub enum ChangeCommentError {
     #[error("There is no comment with {comment_id:?} as id.")]
     CommentNotFound {
         comment_id: u64,
@@ -1,20 +1,46 @@
 use crate::repositories::Repository;
-use crate::{DatabaseError, Profile, ProfileView, User};
+use crate::validation::MAX_COMMENT_LENGTH;
+use crate::{DatabaseError, Page, Profile, ProfileView, User, ValidationError};
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct CommentContent(pub String);
 
+impl CommentContent {
+    /// Fails if the body is blank or too long.
+    pub fn new(body: String) -> Result<Self, ValidationError> {
+        let mut errors = ValidationError::default();
+        errors.check_not_blank("body", &body);
+        errors.check_max_length("body", &body, MAX_COMMENT_LENGTH);
+        errors.or_ok(CommentContent(body))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Comment {
     pub id: u64,
+    pub article_id: Uuid,
+    /// The comment this one replies to, if any.
+    pub parent_id: Option<u64>,
     pub author: Profile,
     pub body: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When the body was last edited, if ever.
+    pub edited_at: Option<DateTime<Utc>>,
+    /// Deleted comments with replies are kept, with an empty body, to hold their thread.
+    pub deleted: bool,
 }
 
 impl Comment {
+    /// Whether the body changed since the comment was posted.
+    pub fn edited(&self) -> bool {
+        self.edited_at.is_some()
+    }
+
     pub async fn view(
         self,
         viewer: &User,
@@ -23,22 +49,94 @@ impl Comment {
         let author_view = repository
             .get_profile_view(viewer, &self.author.username)
             .await?;
-        let view = CommentView {
+        Ok(self.with_author(author_view))
+    }
+
+    /// The view of the comment for the viewer of `author`.
+    pub fn with_author(self, author: ProfileView) -> CommentView {
+        CommentView {
             id: self.id,
-            author: author_view,
+            article_id: self.article_id,
+            parent_id: self.parent_id,
+            author,
             body: self.body,
             created_at: self.created_at,
             updated_at: self.updated_at,
-        };
-        Ok(view)
+            edited_at: self.edited_at,
+            deleted: self.deleted,
+        }
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct CommentView {
     pub id: u64,
+    pub article_id: Uuid,
+    pub parent_id: Option<u64>,
     pub author: ProfileView,
     pub body: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub deleted: bool,
+}
+
+impl CommentView {
+    /// Whether the body changed since the comment was posted.
+    pub fn edited(&self) -> bool {
+        self.edited_at.is_some()
+    }
+}
+
+/// Comments are paginated by thread: `limit`, `offset` and `cursor` count top-level comments,
+/// each of them coming with its first `max_replies` replies.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CommentQuery {
+    /// Every thread is returned if `None`.
+    pub limit: Option<u64>,
+    /// Skips the given number of threads past `cursor`, if any.
+    pub offset: u64,
+    /// Starts the page right after the top-level comment with this id.
+    pub cursor: Option<u64>,
+    pub order: CommentOrder,
+    /// The number of replies returned per thread, however deep, the first posted: all of
+    /// them if `None`. Parents are posted before their replies, so none is left out.
+    pub max_replies: Option<u64>,
+}
+
+/// The order top-level comments are listed in: replies always follow their posting order.
+#[derive(Default, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentOrder {
+    /// The oldest first.
+    #[default]
+    Asc,
+    /// The most recent first.
+    Desc,
+}
+
+/// A page of threads, with the cursor to the next page if there is one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommentPage {
+    /// The top-level comments, in the requested order, followed by their replies in
+    /// posting order. The total is the number of top-level comments.
+    pub comments: Page<Comment>,
+    pub next_cursor: Option<u64>,
+    /// The number of replies to each top-level comment of the page, however deep, including
+    /// those past `max_replies`. Threads without replies are left out.
+    pub replies_counts: HashMap<u64, u64>,
+}
+
+impl CommentQuery {
+    /// Trims `top_level`, fetched with one more comment than `limit` to tell whether there
+    /// is a next page, returning the cursor to that page.
+    pub fn next_cursor(&self, top_level: &mut Vec<Comment>) -> Option<u64> {
+        match self.limit {
+            Some(limit) if top_level.len() as u64 > limit => {
+                top_level.truncate(limit as usize);
+                top_level.last().map(|c| c.id)
+            }
+            _ => None,
+        }
+    }
 }
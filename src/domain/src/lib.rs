@@ -1,10 +1,17 @@
 pub mod articles;
 pub mod comments;
 pub mod errors;
+pub mod pagination;
 pub mod repositories;
+pub mod sessions;
 pub mod users;
+pub mod validation;
 
+#[allow(ambiguous_glob_reexports)]
 pub use articles::*;
 pub use comments::*;
 pub use errors::*;
+pub use pagination::*;
+pub use sessions::*;
 pub use users::*;
+pub use validation::ValidationError;
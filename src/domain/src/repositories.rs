@@ -1,10 +1,11 @@
 use crate::{
-    Article, ArticleContent, ArticleQuery, ArticleUpdate, ArticleView, Comment, CommentContent,
-    DatabaseError, DeleteCommentError, FavoriteOutcome, FeedQuery, GetArticleError, GetUserError,
-    LoginError, Profile, ProfileView, PublishArticleError, SignUp, SignUpError, UnfavoriteOutcome,
-    User, UserUpdate,
+    Article, ArticleContent, ArticleQuery, ArticleUpdate, ArticleView, ChangeCommentError, Comment,
+    CommentContent, CommentPage, CommentQuery, CommentView, DatabaseError, FavoriteOutcome,
+    FeedPage, FeedQuery, GetArticleError, GetSessionError, GetUserError, LoginError, Page, Profile,
+    ProfileView, PublishArticleError, RefreshSessionError, RefreshToken, SearchHit, SearchText,
+    Session, SignUp, SignUpError, TagCount, UnfavoriteOutcome, UpdateUserError, User, UserUpdate,
 };
-use std::collections::HashSet;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 #[async_trait::async_trait]
@@ -15,6 +16,8 @@ pub trait Repository: Send + Sync {
         author: &User,
     ) -> Result<Article, PublishArticleError>;
     async fn get_article_by_slug(&self, slug: &str) -> Result<Article, GetArticleError>;
+    /// The slug now used by the article that was renamed away from `old_slug`, if any.
+    async fn get_current_slug(&self, old_slug: &str) -> Result<Option<String>, DatabaseError>;
     async fn get_article_view(
         &self,
         viewer: &User,
@@ -25,18 +28,57 @@ pub trait Repository: Send + Sync {
         viewer: &User,
         articles: Vec<Article>,
     ) -> Result<Vec<ArticleView>, DatabaseError>;
-    async fn find_articles(&self, query: ArticleQuery) -> Result<Vec<Article>, DatabaseError>;
-    async fn feed(&self, user: &User, query: FeedQuery) -> Result<Vec<ArticleView>, DatabaseError>;
+    async fn find_articles(&self, query: ArticleQuery) -> Result<Page<Article>, DatabaseError>;
+    /// Articles matching both `text` and the filters of `query`, the most relevant first.
+    ///
+    /// `query.sort` is ignored.
+    async fn search_articles(
+        &self,
+        text: &SearchText,
+        query: ArticleQuery,
+    ) -> Result<Page<SearchHit>, DatabaseError>;
+    /// The most recent articles by the authors `user` follows.
+    async fn feed(&self, user: &User, query: FeedQuery) -> Result<FeedPage, DatabaseError>;
     async fn delete_article(&self, article: &Article) -> Result<(), DatabaseError>;
+    /// Posts a comment on the article, as a reply to the `parent_id` comment if set.
     async fn comment_article(
         &self,
         user: &User,
         article: &Article,
         comment: CommentContent,
+        parent_id: Option<u64>,
     ) -> Result<Comment, DatabaseError>;
-    async fn get_comment(&self, comment_id: u64) -> Result<Comment, DeleteCommentError>;
-    async fn get_comments(&self, article: &Article) -> Result<Vec<Comment>, DatabaseError>;
-    async fn delete_comment(&self, comment_id: u64) -> Result<(), DeleteCommentError>;
+    /// Fails with [`ChangeCommentError::CommentNotFound`] if the comment is on another article.
+    async fn get_comment(
+        &self,
+        article: &Article,
+        comment_id: u64,
+    ) -> Result<Comment, ChangeCommentError>;
+    /// A page of the threads on the article: deleted comments with replies are included.
+    async fn get_comments(
+        &self,
+        article: &Article,
+        query: CommentQuery,
+    ) -> Result<CommentPage, DatabaseError>;
+    async fn get_comments_views(
+        &self,
+        viewer: &User,
+        comments: Vec<Comment>,
+    ) -> Result<Vec<CommentView>, DatabaseError>;
+    /// Deletes the comment for good: its replies, if any, lose their parent. Tombstones left
+    /// without replies are deleted too, up the thread.
+    async fn delete_comment(&self, comment_id: u64) -> Result<(), ChangeCommentError>;
+    async fn has_replies(&self, comment_id: u64) -> Result<bool, DatabaseError>;
+    /// Empties the comment and flags it as deleted, keeping it in its thread.
+    async fn tombstone_comment(&self, comment_id: u64) -> Result<(), ChangeCommentError>;
+    /// Replaces the body of the comment, bumping its `updated_at`.
+    async fn update_comment(
+        &self,
+        comment_id: u64,
+        content: CommentContent,
+    ) -> Result<Comment, ChangeCommentError>;
+    /// Moves the article to a new slug if `update` asks the slug to follow a new title,
+    /// remembering the old one.
     async fn update_article(
         &self,
         article: Article,
@@ -53,7 +95,7 @@ pub trait Repository: Send + Sync {
         user: &User,
     ) -> Result<UnfavoriteOutcome, DatabaseError>;
     async fn sign_up(&self, sign_up: SignUp) -> Result<User, SignUpError>;
-    async fn update_user(&self, user: User, update: UserUpdate) -> Result<User, DatabaseError>;
+    async fn update_user(&self, user: User, update: UserUpdate) -> Result<User, UpdateUserError>;
     async fn get_user_by_id(&self, user_id: Uuid) -> Result<User, GetUserError>;
     async fn get_user_by_email_and_password(
         &self,
@@ -72,5 +114,32 @@ pub trait Repository: Send + Sync {
         follower: &User,
         to_be_unfollowed: &Profile,
     ) -> Result<(), DatabaseError>;
-    async fn get_tags(&self) -> Result<HashSet<String>, DatabaseError>;
+    /// Tags used by at least one article, the most used first and ties broken by name.
+    async fn get_tags(&self, limit: Option<u64>) -> Result<Vec<TagCount>, DatabaseError>;
+    /// Only the hash of `refresh_token` is stored.
+    async fn create_session(
+        &self,
+        user: &User,
+        refresh_token: &RefreshToken,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Session, DatabaseError>;
+    async fn get_session(&self, session_id: Uuid) -> Result<Session, GetSessionError>;
+    async fn get_session_by_refresh_token(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> Result<Session, GetSessionError>;
+    /// Replaces the `current` refresh token of `session`, which then expires at `expires_at`.
+    ///
+    /// It fails with `InvalidRefreshToken` if `current` has already been replaced, e.g. by a
+    /// concurrent refresh, or if the session has been revoked in the meantime.
+    async fn rotate_refresh_token(
+        &self,
+        session: &Session,
+        current: &RefreshToken,
+        refresh_token: &RefreshToken,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Session, RefreshSessionError>;
+    async fn revoke_session(&self, session_id: Uuid) -> Result<(), DatabaseError>;
+    /// Revokes all the sessions of a user, logging them out everywhere.
+    async fn revoke_sessions(&self, user: &User) -> Result<(), DatabaseError>;
 }
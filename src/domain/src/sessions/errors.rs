@@ -0,0 +1,27 @@
+use crate::DatabaseError;
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetSessionError {
+    #[error("There is no session with id {session_id:?}.")]
+    NotFound {
+        session_id: Uuid,
+        #[source]
+        source: DatabaseError,
+    },
+    #[error("There is no session matching the refresh token.")]
+    NotFoundByRefreshToken {
+        #[source]
+        source: DatabaseError,
+    },
+    #[error("Something went wrong.")]
+    DatabaseError(#[from] DatabaseError),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RefreshSessionError {
+    #[error("The refresh token is unknown, expired or belongs to a revoked session.")]
+    InvalidRefreshToken,
+    #[error("Something went wrong.")]
+    DatabaseError(#[from] DatabaseError),
+}
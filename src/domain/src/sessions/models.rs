@@ -0,0 +1,106 @@
+use crate::repositories::Repository;
+use crate::{DatabaseError, GetSessionError, RefreshSessionError, User};
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// A user logged in on some device.
+///
+/// Access tokens refer to the session they were issued for: once the session is revoked
+/// they are rejected, even if they have not expired yet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// The refresh token can not be exchanged for new access tokens after this instant.
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Secret handed out to the client to get new access tokens without logging in again.
+///
+/// Only its hash is stored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefreshToken(String);
+
+impl RefreshToken {
+    /// A new random refresh token.
+    pub fn generate() -> Self {
+        RefreshToken(format!(
+            "{}{}",
+            Uuid::new_v4().to_simple(),
+            Uuid::new_v4().to_simple()
+        ))
+    }
+
+    /// Wraps the refresh token sent back by a client.
+    pub fn from_clear_text(clear_text: String) -> Self {
+        RefreshToken(clear_text)
+    }
+
+    /// Returns the refresh token to hand out to the client.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the SHA-256 of the token, hex-encoded.
+    ///
+    /// Refresh tokens are random and long enough not to need a slow, salted hash.
+    pub fn hash(&self) -> String {
+        format!("{:x}", Sha256::digest(self.0.as_bytes()))
+    }
+}
+
+impl Session {
+    /// Opens a new session for `user`, valid for `lifetime`.
+    pub async fn start(
+        user: &User,
+        lifetime: Duration,
+        repository: &impl Repository,
+    ) -> Result<(Session, RefreshToken), DatabaseError> {
+        let refresh_token = RefreshToken::generate();
+        let expires_at = Utc::now() + lifetime;
+        let session = repository
+            .create_session(user, &refresh_token, expires_at)
+            .await?;
+        Ok((session, refresh_token))
+    }
+
+    /// Exchanges a refresh token for a new one, extending the session by `lifetime`.
+    ///
+    /// The old refresh token can not be used again.
+    pub async fn refresh(
+        refresh_token: &RefreshToken,
+        lifetime: Duration,
+        repository: &impl Repository,
+    ) -> Result<(Session, RefreshToken), RefreshSessionError> {
+        let session = match repository.get_session_by_refresh_token(refresh_token).await {
+            Ok(session) => session,
+            Err(GetSessionError::DatabaseError(e)) => return Err(e.into()),
+            Err(_) => return Err(RefreshSessionError::InvalidRefreshToken),
+        };
+        if !session.is_active() {
+            return Err(RefreshSessionError::InvalidRefreshToken);
+        }
+        let new_refresh_token = RefreshToken::generate();
+        let session = repository
+            .rotate_refresh_token(
+                &session,
+                refresh_token,
+                &new_refresh_token,
+                Utc::now() + lifetime,
+            )
+            .await?;
+        Ok((session, new_refresh_token))
+    }
+
+    /// A session is active until it is revoked or it expires.
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now()
+    }
+
+    pub async fn revoke(&self, repository: &impl Repository) -> Result<(), DatabaseError> {
+        repository.revoke_session(self.id).await
+    }
+}
@@ -1,4 +1,4 @@
-use crate::DatabaseError;
+use crate::{DatabaseError, ValidationError};
 use uuid::Uuid;
 
 #[derive(thiserror::Error, Debug)]
@@ -27,7 +27,7 @@ pub enum GetUserError {
 #[derive(thiserror::Error, Debug)]
 pub enum LoginError {
     #[error("There is no user with the email and password you specified")]
-    NotFound,
+    InvalidCredentials,
     #[error("Failed to process password")]
     PasswordError(#[from] PasswordError),
     #[error("Something went wrong.")]
@@ -36,6 +36,45 @@ pub enum LoginError {
 
 #[derive(thiserror::Error, Debug)]
 pub enum SignUpError {
+    #[error(transparent)]
+    Invalid(#[from] ValidationError),
+    #[error("The username {username:?} is already taken.")]
+    UsernameTaken { username: String },
+    #[error("The email {email:?} is already taken.")]
+    EmailTaken { email: String },
+    #[error("Failed to process password")]
+    PasswordError(#[from] PasswordError),
+    #[error("Something went wrong.")]
+    DatabaseError(#[from] DatabaseError),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum UpdateUserError {
+    #[error(transparent)]
+    Invalid(#[from] ValidationError),
+    #[error("There is no user with id {user_id:?}.")]
+    UserNotFound {
+        user_id: Uuid,
+        #[source]
+        source: GetUserError,
+    },
+    #[error("The username {username:?} is already taken.")]
+    UsernameTaken { username: String },
+    #[error("The email {email:?} is already taken.")]
+    EmailTaken { email: String },
+    #[error("Failed to process password")]
+    PasswordError(#[from] PasswordError),
     #[error("Something went wrong.")]
     DatabaseError(#[from] DatabaseError),
 }
+
+impl From<GetUserError> for UpdateUserError {
+    fn from(e: GetUserError) -> Self {
+        match e {
+            GetUserError::NotFound { user_id, .. } => {
+                UpdateUserError::UserNotFound { user_id, source: e }
+            }
+            e => UpdateUserError::DatabaseError(e.into()),
+        }
+    }
+}
@@ -1,11 +1,18 @@
 use crate::repositories::Repository;
+use crate::validation::MAX_BIO_LENGTH;
 use crate::{
-    Article, ArticleContent, ArticleUpdate, ArticleView, ChangeArticleError, Comment,
-    CommentContent, CommentView, DatabaseError, DeleteCommentError, PasswordError,
-    PublishArticleError,
+    Article, ArticleContent, ArticleUpdate, ArticleView, ChangeArticleError, ChangeCommentError,
+    Comment, CommentContent, CommentView, DatabaseError, LoginError, Page, PasswordError,
+    PublishArticleError, SignUpError, UpdateUserError, ValidationError,
 };
+use chrono::{DateTime, TimeZone, Utc};
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
+/// The hash of a password nobody has, with the same cost as the real ones.
+const DUMMY_HASH: &str = "$2b$04$9s8O14DzgFlIHaOZsL6TcuXyHDHfgK9wUAqOlQTAMaMHrwM0a77Mu";
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Password(String);
 
@@ -33,6 +40,30 @@ impl Password {
     pub fn verify(&self, clear_text_password: &str) -> Result<bool, PasswordError> {
         Ok(bcrypt::verify(clear_text_password, &self.0)?)
     }
+
+    /// Check a login attempt against `self`, a wrong password giving `InvalidCredentials`.
+    ///
+    /// So does a hash bcrypt cannot process, after as long as a wrong password would take.
+    pub fn check(&self, clear_text_password: &str) -> Result<(), LoginError> {
+        match self.verify(clear_text_password) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(LoginError::InvalidCredentials),
+            Err(_) => Err(Self::reject(clear_text_password)),
+        }
+    }
+
+    /// Reject a login attempt for an unknown email.
+    ///
+    /// The password is still checked against a dummy hash, so that the response time doesn't
+    /// reveal whether an account exists.
+    pub fn reject_unknown_user(clear_text_password: &str) -> LoginError {
+        Self::reject(clear_text_password)
+    }
+
+    fn reject(clear_text_password: &str) -> LoginError {
+        let _ = bcrypt::verify(clear_text_password, DUMMY_HASH);
+        LoginError::InvalidCredentials
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -49,6 +80,22 @@ pub struct SignUp {
     pub password: Password,
 }
 
+impl SignUp {
+    /// Validates the input, then hashes the password.
+    pub fn new(username: String, email: String, password: &str) -> Result<Self, SignUpError> {
+        let mut errors = ValidationError::default();
+        errors.check_username(&username);
+        errors.check_email(&email);
+        errors.check_password(password);
+        errors.or_ok(())?;
+        Ok(SignUp {
+            username,
+            email,
+            password: Password::from_clear_text(password.to_string())?,
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct User {
     pub id: Uuid,
@@ -65,6 +112,47 @@ pub struct UserUpdate {
     pub bio: Option<String>,
 }
 
+impl UserUpdate {
+    /// Validates the fields being changed, then hashes the new password if there is one.
+    ///
+    /// A blank image removes the current one: that is what clients send when it is cleared.
+    pub fn new(
+        email: Option<String>,
+        username: Option<String>,
+        password: Option<&str>,
+        image: Option<String>,
+        bio: Option<String>,
+    ) -> Result<Self, UpdateUserError> {
+        let mut errors = ValidationError::default();
+        if let Some(email) = &email {
+            errors.check_email(email);
+        }
+        if let Some(username) = &username {
+            errors.check_username(username);
+        }
+        if let Some(password) = password {
+            errors.check_password(password);
+        }
+        let image = image.filter(|image| !image.trim().is_empty());
+        if let Some(image) = &image {
+            errors.check_image(image);
+        }
+        if let Some(bio) = &bio {
+            errors.check_max_length("bio", bio, MAX_BIO_LENGTH);
+        }
+        errors.or_ok(())?;
+        Ok(UserUpdate {
+            email,
+            username,
+            password: password
+                .map(|p| Password::from_clear_text(p.to_string()))
+                .transpose()?,
+            image,
+            bio,
+        })
+    }
+}
+
 impl User {
     pub async fn publish(
         &self,
@@ -80,11 +168,12 @@ impl User {
         update: ArticleUpdate,
         repository: &impl Repository,
     ) -> Result<Article, ChangeArticleError> {
-        if article.author.username != self.profile.username {
-            return Err(ChangeArticleError::Forbidden {
-                slug: article.slug,
-                user_id: self.id,
-            });
+        self.check_article_author(&article)?;
+        if let Some(tag_list) = &update.tag_list {
+            // Adding tags can push an article over the limit
+            let mut errors = ValidationError::default();
+            errors.check_tags(&tag_list.apply(&article.content.tag_list));
+            errors.or_ok(())?;
         }
         let updated_article = repository.update_article(article, update).await?;
         Ok(updated_article)
@@ -94,7 +183,7 @@ impl User {
         self,
         update: UserUpdate,
         repository: &impl Repository,
-    ) -> Result<Self, DatabaseError> {
+    ) -> Result<Self, UpdateUserError> {
         repository.update_user(self, update).await
     }
 
@@ -103,14 +192,19 @@ impl User {
         article: Article,
         repository: &impl Repository,
     ) -> Result<(), ChangeArticleError> {
-        // You can only delete your own articles
+        self.check_article_author(&article)?;
+        Ok(repository.delete_article(&article).await?)
+    }
+
+    /// You can only change your own articles.
+    pub fn check_article_author(&self, article: &Article) -> Result<(), ChangeArticleError> {
         if article.author.username != self.profile.username {
             return Err(ChangeArticleError::Forbidden {
-                slug: article.slug,
+                slug: article.slug.clone(),
                 user_id: self.id,
             });
         }
-        Ok(repository.delete_article(&article).await?)
+        Ok(())
     }
 
     pub async fn comment(
@@ -119,36 +213,86 @@ impl User {
         comment: CommentContent,
         repository: &impl Repository,
     ) -> Result<CommentView, ChangeArticleError> {
-        let posted_comment = repository.comment_article(self, article, comment).await?;
-        let view = CommentView {
-            id: posted_comment.id,
-            author: ProfileView {
-                profile: posted_comment.author,
-                // Users always self-follow
-                following: true,
-                viewer: self.id,
-            },
-            body: posted_comment.body,
-            created_at: posted_comment.created_at,
-            updated_at: posted_comment.updated_at,
-        };
-        Ok(view)
+        let posted_comment = repository
+            .comment_article(self, article, comment, None)
+            .await?;
+        Ok(self.own_comment_view(posted_comment))
+    }
+
+    /// Replies to `parent`, which must be a comment on `article` that has not been deleted.
+    pub async fn reply(
+        &self,
+        article: &Article,
+        parent: &Comment,
+        comment: CommentContent,
+        repository: &impl Repository,
+    ) -> Result<CommentView, ChangeCommentError> {
+        if parent.article_id != article.id || parent.deleted {
+            return Err(ChangeCommentError::CommentNotFound {
+                comment_id: parent.id,
+                source: anyhow::anyhow!("No comment to reply to").into(),
+            });
+        }
+
+        let posted_comment = repository
+            .comment_article(self, article, comment, Some(parent.id))
+            .await?;
+        Ok(self.own_comment_view(posted_comment))
     }
 
+    /// Deletes the comment, unless it has replies: it gets emptied and flagged as deleted
+    /// instead, to keep the thread together.
     pub async fn delete_comment(
         &self,
         comment: Comment,
         repository: &impl Repository,
-    ) -> Result<(), DeleteCommentError> {
-        // You can only delete your own comments
+    ) -> Result<(), ChangeCommentError> {
+        self.check_comment_author(&comment)?;
+
+        if repository.has_replies(comment.id).await? {
+            repository.tombstone_comment(comment.id).await
+        } else {
+            repository.delete_comment(comment.id).await
+        }
+    }
+
+    pub async fn edit_comment(
+        &self,
+        comment: Comment,
+        content: CommentContent,
+        repository: &impl Repository,
+    ) -> Result<CommentView, ChangeCommentError> {
+        self.check_comment_author(&comment)?;
+
+        let edited = repository.update_comment(comment.id, content).await?;
+        Ok(self.own_comment_view(edited))
+    }
+
+    /// You can only change your own comments, as long as they are not deleted.
+    fn check_comment_author(&self, comment: &Comment) -> Result<(), ChangeCommentError> {
+        if comment.deleted {
+            return Err(ChangeCommentError::CommentNotFound {
+                comment_id: comment.id,
+                source: anyhow::anyhow!("The comment has been deleted").into(),
+            });
+        }
         if comment.author.username != self.profile.username {
-            return Err(DeleteCommentError::Forbidden {
+            return Err(ChangeCommentError::Forbidden {
                 comment_id: comment.id,
                 user_id: self.id,
             });
         }
+        Ok(())
+    }
 
-        repository.delete_comment(comment.id).await
+    fn own_comment_view(&self, comment: Comment) -> CommentView {
+        let author = ProfileView {
+            profile: comment.author.clone(),
+            // Users always self-follow
+            following: true,
+            viewer: self.id,
+        };
+        comment.with_author(author)
     }
 
     pub async fn favorite(
@@ -161,6 +305,7 @@ impl User {
             FavoriteOutcome::AlreadyAFavorite => article.favorites_count,
         };
         let article_view = ArticleView {
+            id: article.id,
             content: article.content,
             slug: article.slug,
             author: repository
@@ -180,10 +325,12 @@ impl User {
         repository: &impl Repository,
     ) -> Result<ArticleView, DatabaseError> {
         let n_favorites = match repository.unfavorite(&article, self).await? {
-            UnfavoriteOutcome::WasAFavorite => article.favorites_count - 1,
+            // The counter is denormalized and may have drifted: never let it go below zero
+            UnfavoriteOutcome::WasAFavorite => article.favorites_count.saturating_sub(1),
             UnfavoriteOutcome::WasNotAFavorite => article.favorites_count,
         };
         let article_view = ArticleView {
+            id: article.id,
             content: article.content,
             slug: article.slug,
             author: repository
@@ -229,9 +376,17 @@ impl User {
         &self,
         query: FeedQuery,
         repository: &impl Repository,
-    ) -> Result<Vec<ArticleView>, DatabaseError> {
+    ) -> Result<FeedPage, DatabaseError> {
         repository.feed(self, query).await
     }
+
+    /// Revokes all the sessions of the user: the tokens handed out so far are rejected.
+    pub async fn log_out_everywhere(
+        &self,
+        repository: &impl Repository,
+    ) -> Result<(), DatabaseError> {
+        repository.revoke_sessions(self).await
+    }
 }
 
 pub enum FavoriteOutcome {
@@ -255,5 +410,76 @@ pub struct ProfileView {
 #[derive(Debug, Clone, PartialEq)]
 pub struct FeedQuery {
     pub limit: u64,
+    /// Skips the given number of articles past `cursor`, if any.
     pub offset: u64,
+    /// Starts the page right after the article the cursor was taken from.
+    pub cursor: Option<FeedCursor>,
+}
+
+/// A position in the feed, which stays valid while new articles get published: pages taken
+/// from it neither skip nor repeat articles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl FeedCursor {
+    /// The cursor to the articles following `article` in the feed.
+    pub fn after(article: &ArticleView) -> Self {
+        Self {
+            created_at: article.metadata.created_at,
+            id: article.id,
+        }
+    }
+}
+
+/// Cursors are handed to clients as opaque strings.
+impl fmt::Display for FeedCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.created_at.timestamp_nanos();
+        write!(f, "{:x}-{}", nanos, self.id.to_simple())
+    }
+}
+
+impl FromStr for FeedCursor {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            let mut errors = ValidationError::default();
+            errors.add("cursor", "is invalid");
+            errors
+        };
+        let (nanos, id) = s.split_once('-').ok_or_else(invalid)?;
+        let nanos = i64::from_str_radix(nanos, 16).map_err(|_| invalid())?;
+        let created_at = Utc.timestamp_nanos(nanos);
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+        Ok(Self { created_at, id })
+    }
+}
+
+/// A page of the feed, with the cursor to the next page if there is one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedPage {
+    pub articles: Page<ArticleView>,
+    pub next_cursor: Option<FeedCursor>,
+}
+
+impl FeedPage {
+    /// Takes up to `limit` articles out of `articles`, which should hold one more article
+    /// when there is a next page.
+    pub fn new(mut articles: Vec<ArticleView>, limit: u64, total: u64) -> Self {
+        let next_cursor = match articles.len() as u64 > limit {
+            true => {
+                articles.truncate(limit as usize);
+                articles.last().map(FeedCursor::after)
+            }
+            false => None,
+        };
+        Self {
+            articles: Page::new(articles, total),
+            next_cursor,
+        }
+    }
 }
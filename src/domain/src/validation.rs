@@ -0,0 +1,157 @@
+//! Field-level checks on the input provided by users.
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub const MAX_USERNAME_LENGTH: usize = 64;
+pub const MAX_EMAIL_LENGTH: usize = 255;
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+/// bcrypt ignores whatever comes after the first 72 bytes.
+pub const MAX_PASSWORD_BYTES: usize = 72;
+pub const MAX_BIO_LENGTH: usize = 2048;
+pub const MAX_IMAGE_LENGTH: usize = 2048;
+pub const MAX_TITLE_LENGTH: usize = 255;
+pub const MAX_DESCRIPTION_LENGTH: usize = 1024;
+pub const MAX_BODY_LENGTH: usize = 65_536;
+pub const MAX_TAG_LENGTH: usize = 64;
+pub const MAX_TAGS: usize = 20;
+pub const MAX_COMMENT_LENGTH: usize = 10_000;
+pub const MAX_SEARCH_LENGTH: usize = 256;
+
+/// All the problems found in some input, grouped by field.
+#[derive(thiserror::Error, Clone, Debug, Default, PartialEq)]
+pub struct ValidationError {
+    errors: BTreeMap<String, Vec<String>>,
+}
+
+impl ValidationError {
+    /// Problems found so far, grouped by field.
+    pub fn errors(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.errors
+    }
+
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors
+            .entry(field.to_string())
+            .or_default()
+            .push(message.into());
+    }
+
+    /// Returns `value` if no problem has been found.
+    pub fn or_ok<T>(self, value: T) -> Result<T, ValidationError> {
+        if self.errors.is_empty() {
+            Ok(value)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub(crate) fn check_not_blank(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.add(field, "can't be blank");
+        }
+    }
+
+    pub(crate) fn check_max_length(&mut self, field: &str, value: &str, max: usize) {
+        if value.chars().count() > max {
+            self.add(
+                field,
+                format!("is too long (maximum is {} characters)", max),
+            );
+        }
+    }
+
+    pub(crate) fn check_username(&mut self, username: &str) {
+        self.check_not_blank("username", username);
+        self.check_max_length("username", username, MAX_USERNAME_LENGTH);
+        let allowed = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_');
+        if !username.chars().all(allowed) {
+            self.add(
+                "username",
+                "can only contain letters, digits, '.', '-' and '_'",
+            );
+        }
+    }
+
+    pub(crate) fn check_email(&mut self, email: &str) {
+        self.check_not_blank("email", email);
+        self.check_max_length("email", email, MAX_EMAIL_LENGTH);
+        if !email.trim().is_empty() && !is_email(email) {
+            self.add("email", "is invalid");
+        }
+    }
+
+    pub(crate) fn check_password(&mut self, password: &str) {
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            self.add(
+                "password",
+                format!(
+                    "is too short (minimum is {} characters)",
+                    MIN_PASSWORD_LENGTH
+                ),
+            );
+        }
+        if password.len() > MAX_PASSWORD_BYTES {
+            self.add(
+                "password",
+                format!("is too long (maximum is {} bytes)", MAX_PASSWORD_BYTES),
+            );
+        }
+    }
+
+    pub(crate) fn check_image(&mut self, image: &str) {
+        self.check_max_length("image", image, MAX_IMAGE_LENGTH);
+        if !(image.starts_with("https://") || image.starts_with("http://")) {
+            self.add("image", "must be an http(s) URL");
+        }
+    }
+
+    pub(crate) fn check_tags(&mut self, tags: &[String]) {
+        if tags.len() > MAX_TAGS {
+            self.add(
+                "tagList",
+                format!("has too many tags (maximum is {})", MAX_TAGS),
+            );
+        }
+        for tag in tags {
+            if tag.trim().is_empty() {
+                self.add("tagList", "can't contain blank tags");
+            }
+            if tag.chars().count() > MAX_TAG_LENGTH {
+                self.add(
+                    "tagList",
+                    format!(
+                        "can't contain tags longer than {} characters",
+                        MAX_TAG_LENGTH
+                    ),
+                );
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid input:")?;
+        for (field, messages) in &self.errors {
+            write!(f, " {} {};", field, messages.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A deliberately loose check: a non-empty local part, an `@` and a dotted domain.
+fn is_email(email: &str) -> bool {
+    if email.chars().any(char::is_whitespace) {
+        return false;
+    }
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+        }
+        None => false,
+    }
+}
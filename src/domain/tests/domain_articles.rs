@@ -4,11 +4,15 @@ use crate::helpers::{create_article, create_user, test_db};
 use fake::fake;
 use helpers::generate;
 use realworld_domain::repositories::Repository as RepositoryTrait;
-use realworld_domain::ArticleUpdate;
+use realworld_domain::validation::MAX_TAGS;
+use realworld_domain::{
+    normalize_tags, slug_candidates, slugify, ArticleUpdate, ChangeArticleError, TagListUpdate,
+    MAX_SLUG_LENGTH,
+};
 
 #[tokio::test]
 async fn slugs_must_be_unique() {
-    let db = test_db("slugs_must_be_unique").await;
+    let db = test_db();
 
     let author = create_user(&db).await.0;
     let first_draft = generate::article_content();
@@ -16,26 +20,61 @@ async fn slugs_must_be_unique() {
     // Two article drafts, with identical title => identical slug
     assert_eq!(first_draft.slug(), second_draft.slug());
 
-    let _expected_slug = first_draft.slug();
+    let first = author.publish(first_draft, &db.0).await.unwrap();
+    // Publishing the second draft picks the next free slug
+    let second = author.publish(second_draft, &db.0).await.unwrap();
+    assert_eq!(second.slug, format!("{}-2", first.slug));
+}
 
-    let result = author.publish(first_draft, &db.0).await;
-    assert!(result.is_ok());
+#[test]
+fn slugs_are_url_safe() {
+    assert_eq!(
+        slugify("How to train your dragon"),
+        "how-to-train-your-dragon"
+    );
+    assert_eq!(
+        slugify("  What's new in Rust 1.60?!  "),
+        "what-s-new-in-rust-1-60"
+    );
+    assert_eq!(slugify("Crème brûlée à Paris"), "creme-brulee-a-paris");
+    assert_eq!(slugify("Straße / Ærø"), "strasse-aero");
+    assert_eq!(slugify("北京"), "bei-jing");
+    assert_eq!(
+        slugify("<script>alert(1)</script>"),
+        "script-alert-1-script"
+    );
+    assert_eq!(slugify("?!"), "article");
 
-    // Publishing the second draft fails
-    let result = author.publish(second_draft, &db.0).await;
-    assert!(result.is_err());
+    let long = slugify(&"word ".repeat(100));
+    assert!(long.len() <= MAX_SLUG_LENGTH);
+    assert!(!long.ends_with('-'));
+}
 
-    // With the appropriate error variant
-    // TODO check the correct error
-    // match result.unwrap_err() {
-    //     PublishArticleError::DuplicatedSlug { slug, source: _ } => assert_eq!(expected_slug, slug),
-    //     _ => panic!("Unexpected error"),
-    // }
+#[test]
+fn slug_candidates_start_with_numeric_suffixes() {
+    let candidates: Vec<String> = slug_candidates("dragons".to_string()).take(10).collect();
+    assert_eq!(
+        candidates[..4],
+        ["dragons", "dragons-2", "dragons-3", "dragons-4"]
+    );
+    let unique: std::collections::HashSet<&String> = candidates.iter().collect();
+    assert_eq!(unique.len(), candidates.len());
+}
+
+#[test]
+fn slug_candidates_never_run_out() {
+    let slug = "a".repeat(MAX_SLUG_LENGTH);
+    let candidates: Vec<String> = slug_candidates(slug).take(1000).collect();
+    assert_eq!(candidates.len(), 1000);
+    let unique: std::collections::HashSet<&String> = candidates.iter().collect();
+    assert_eq!(unique.len(), candidates.len());
+    // `articles.slug` holds up to 255 characters
+    assert!(candidates.iter().all(|s| s.len() <= 255));
 }
 
 #[tokio::test]
 async fn insert_and_retrieve_article() {
-    let db = test_db("insert_and_retrieve_article").await;
+    let db = test_db();
     let author = create_user(&db).await.0;
     let author = db.0.get_user_by_id(author.id).await.unwrap();
     let draft = generate::article_content();
@@ -50,7 +89,7 @@ async fn insert_and_retrieve_article() {
 
 #[tokio::test]
 async fn update_and_retrieve_article() {
-    let db = test_db("update_and_retrieve_article").await;
+    let db = test_db();
 
     let author = create_user(&db).await.0;
     let article = create_article(&db, &author).await;
@@ -59,6 +98,8 @@ async fn update_and_retrieve_article() {
         title: Some(fake!(Lorem.sentence(4, 10)).to_string()),
         description: Some(fake!(Lorem.paragraph(3, 10)).to_string()),
         body: Some(fake!(Lorem.paragraph(10, 5)).to_string()),
+        tag_list: None,
+        slug_follows_title: false,
     };
     let updated_article = author
         .update_article(article, update.clone(), &db.0)
@@ -72,3 +113,63 @@ async fn update_and_retrieve_article() {
     );
     assert_eq!(update.body, updated_article.content.body.into());
 }
+
+#[test]
+fn tags_are_normalized() {
+    let tags = [" Rust", "rust", "WebAssembly ", "", "webassembly", "async"];
+    assert_eq!(
+        normalize_tags(tags.iter().map(|t| t.to_string())),
+        ["rust", "webassembly", "async"]
+    );
+}
+
+#[test]
+fn tag_list_updates() {
+    let current = vec!["rust".to_string(), "async".to_string()];
+    let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+    let update = TagListUpdate {
+        add: tags(&["Tokio", "rust"]),
+        remove: tags(&["ASYNC", "missing"]),
+        ..Default::default()
+    };
+    assert_eq!(update.apply(&current), ["rust", "tokio"]);
+
+    // Replacing happens before adding and removing
+    let update = TagListUpdate {
+        replace: Some(tags(&["Axum", "web"])),
+        add: tags(&["http"]),
+        remove: tags(&["web"]),
+    };
+    assert_eq!(update.apply(&current), ["axum", "http"]);
+
+    let update = TagListUpdate {
+        replace: Some(vec![]),
+        ..Default::default()
+    };
+    assert!(update.apply(&current).is_empty());
+}
+
+#[tokio::test]
+async fn articles_cannot_have_too_many_tags() {
+    let db = test_db();
+    let author = create_user(&db).await.0;
+    let article = create_article(&db, &author).await;
+
+    let add = (0..MAX_TAGS).map(|i| format!("tag-{}", i)).collect();
+    let update = ArticleUpdate::new(
+        None,
+        None,
+        None,
+        Some(TagListUpdate {
+            add,
+            ..Default::default()
+        }),
+    )
+    .unwrap();
+    let result = author.update_article(article, update, &db.0).await;
+    match result {
+        Err(ChangeArticleError::Invalid(e)) => assert!(e.errors().contains_key("tagList")),
+        other => panic!("Unexpected result: {:?}", other),
+    }
+}
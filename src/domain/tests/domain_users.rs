@@ -0,0 +1,18 @@
+use realworld_domain::{LoginError, Password};
+
+#[test]
+fn login_attempts_are_checked_against_the_hash() {
+    let password = Password::from_clear_text("dragons".to_string()).unwrap();
+    assert!(password.check("dragons").is_ok());
+    assert!(matches!(
+        password.check("vikings"),
+        Err(LoginError::InvalidCredentials)
+    ));
+
+    // Hashes bcrypt cannot process do not stand out from wrong passwords
+    let broken = Password::from_hash("not-a-bcrypt-hash".to_string());
+    assert!(matches!(
+        broken.check("dragons"),
+        Err(LoginError::InvalidCredentials)
+    ));
+}
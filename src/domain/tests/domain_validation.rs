@@ -0,0 +1,135 @@
+use realworld_domain::{
+    validation::MAX_SEARCH_LENGTH, ArticleContent, ArticleUpdate, CommentContent, SearchText,
+    SignUp, SignUpError, UpdateUserError, UserUpdate, ValidationError,
+};
+
+fn messages<'a>(e: &'a ValidationError, field: &str) -> &'a [String] {
+    e.errors().get(field).map(Vec::as_slice).unwrap_or_default()
+}
+
+#[test]
+fn valid_sign_up() {
+    let sign_up = SignUp::new(
+        "jake_99".to_string(),
+        "jake@jake.jake".to_string(),
+        "jakejake",
+    )
+    .unwrap();
+    assert_eq!(sign_up.username, "jake_99");
+    assert!(sign_up.password.verify("jakejake").unwrap());
+}
+
+#[test]
+fn every_invalid_sign_up_field_is_reported() {
+    let e = match SignUp::new(" ".to_string(), "not-an-email".to_string(), "short") {
+        Err(SignUpError::Invalid(e)) => e,
+        other => panic!("Unexpected result: {:?}", other),
+    };
+    assert_eq!(messages(&e, "username").len(), 2);
+    assert!(messages(&e, "username").contains(&"can't be blank".to_string()));
+    assert_eq!(messages(&e, "email"), ["is invalid"]);
+    assert_eq!(
+        messages(&e, "password"),
+        ["is too short (minimum is 8 characters)"]
+    );
+}
+
+#[test]
+fn emails_are_checked() {
+    let valid = ["jake@jake.jake", "first.last+tag@sub.example.org"];
+    for email in valid {
+        assert!(SignUp::new("jake".to_string(), email.to_string(), "password").is_ok());
+    }
+    let invalid = [
+        "jake",
+        "@jake.jake",
+        "jake@",
+        "jake@jake",
+        "jake@@jake.jake",
+        "ja ke@jake.jake",
+        "jake@.jake",
+    ];
+    for email in invalid {
+        assert!(
+            SignUp::new("jake".to_string(), email.to_string(), "password").is_err(),
+            "{} should be rejected",
+            email
+        );
+    }
+}
+
+#[test]
+fn user_updates_only_check_the_changed_fields() {
+    let update = UserUpdate::new(None, None, None, None, Some("I like to code.".to_string()));
+    assert!(update.is_ok());
+
+    let update = UserUpdate::new(
+        Some("".to_string()),
+        Some("white space".to_string()),
+        Some("1234"),
+        Some("ftp://image.png".to_string()),
+        Some("a".repeat(2049)),
+    );
+    let e = match update {
+        Err(UpdateUserError::Invalid(e)) => e,
+        other => panic!("Unexpected result: {:?}", other),
+    };
+    let fields: Vec<&str> = e.errors().keys().map(String::as_str).collect();
+    assert_eq!(fields, ["bio", "email", "image", "password", "username"]);
+}
+
+#[test]
+fn blank_images_are_removed() {
+    for blank in ["", "  "] {
+        let update = UserUpdate::new(None, None, None, Some(blank.to_string()), None).unwrap();
+        assert_eq!(update.image, None);
+    }
+}
+
+#[test]
+fn article_content_is_checked() {
+    let content = ArticleContent::new(
+        "How to train your dragon".to_string(),
+        "Ever wonder how?".to_string(),
+        "You have to believe".to_string(),
+        vec!["dragons".to_string()],
+    );
+    assert!(content.is_ok());
+
+    let e = ArticleContent::new(
+        "".to_string(),
+        "d".repeat(1025),
+        "b".repeat(65_537),
+        vec![" ".to_string()],
+    )
+    .unwrap_err();
+    assert_eq!(messages(&e, "title"), ["can't be blank"]);
+    assert_eq!(
+        messages(&e, "description"),
+        ["is too long (maximum is 1024 characters)"]
+    );
+    assert_eq!(
+        messages(&e, "body"),
+        ["is too long (maximum is 65536 characters)"]
+    );
+    assert_eq!(messages(&e, "tagList"), ["can't contain blank tags"]);
+
+    let e = ArticleUpdate::new(Some("  ".to_string()), None, None, None).unwrap_err();
+    assert_eq!(e.errors().keys().collect::<Vec<_>>(), ["title"]);
+    assert!(ArticleUpdate::new(None, None, Some("New body".to_string()), None).is_ok());
+}
+
+#[test]
+fn comments_cannot_be_blank() {
+    assert!(CommentContent::new("Nice!".to_string()).is_ok());
+    let e = CommentContent::new("\n".to_string()).unwrap_err();
+    assert_eq!(messages(&e, "body"), ["can't be blank"]);
+}
+
+#[test]
+fn searches_are_checked() {
+    assert!(SearchText::new("zebra".to_string()).is_ok());
+    let e = SearchText::new(" ".to_string()).unwrap_err();
+    assert_eq!(messages(&e, "q"), ["can't be blank"]);
+    assert!(SearchText::new("a".repeat(MAX_SEARCH_LENGTH + 1)).is_err());
+}
@@ -3,10 +3,7 @@
 pub mod generate;
 pub mod test_db;
 
-use std::path::PathBuf;
-
 use crate::helpers::generate::With;
-use application::configuration::Settings;
 use futures::future::join_all;
 use futures::future::BoxFuture;
 use futures::FutureExt;
@@ -16,10 +13,10 @@ use realworld_domain::User;
 
 use self::test_db::Db;
 
-pub async fn test_db(name: &str) -> Db {
-    let settings = Settings::new(PathBuf::from("../../")).expect("Failed to load configuration");
-    Db::create(settings.database, name).await
+pub fn test_db() -> Db {
+    Db::create()
 }
+
 pub async fn create_users(db: &Db, num_users: i32) -> Vec<(User, String)> {
     let users: Vec<BoxFuture<_>> = (0..num_users).map(|_| create_user(db).boxed()).collect();
 
@@ -37,7 +34,7 @@ pub async fn create_user(db: &Db) -> (realworld_domain::User, String) {
 pub async fn create_articles(repo: &Db, users: Vec<User>) -> Vec<Article> {
     let articles = users
         .iter()
-        .map(|user| create_article(repo, &user).boxed())
+        .map(|user| create_article(repo, user).boxed())
         .collect::<Vec<_>>();
 
     join_all(articles).await
@@ -0,0 +1,4 @@
+mod repository;
+mod store;
+
+pub use repository::Repository;
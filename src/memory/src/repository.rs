@@ -0,0 +1,803 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::store::{not_found, ArticleRow, CommentRow, SessionRow, Store, UserConflict, UserRow};
+use chrono::{DateTime, Utc};
+use realworld_domain::{
+    slug_candidates, ArticleSort, FavoriteOutcome, FeedPage, Page, UnfavoriteOutcome,
+};
+use uuid::Uuid;
+
+/// A `Repository` keeping every entity in process memory.
+///
+/// It mirrors the behaviour of `realworld_db::Repository` and it is meant to be used
+/// where a Postgres instance is not available (e.g. tests).
+/// Clones share the same underlying storage.
+#[derive(Clone, Default)]
+pub struct Repository(Arc<RwLock<Store>>);
+
+impl Repository {
+    pub fn new() -> Repository {
+        Repository::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Store> {
+        self.0.read().expect("The in-memory store is poisoned")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Store> {
+        self.0.write().expect("The in-memory store is poisoned")
+    }
+}
+
+#[async_trait::async_trait]
+impl realworld_domain::repositories::Repository for Repository {
+    async fn publish_article(
+        &self,
+        draft: realworld_domain::ArticleContent,
+        author: &realworld_domain::User,
+    ) -> Result<realworld_domain::Article, realworld_domain::PublishArticleError> {
+        let mut store = self.write();
+        store.user(author.id)?;
+
+        let slug = match draft.slug_candidates().find(|s| !store.slug_is_taken(s)) {
+            Some(slug) => slug,
+            None => {
+                return Err(realworld_domain::PublishArticleError::DuplicatedSlug {
+                    slug: draft.slug(),
+                    source: anyhow::anyhow!("Every slug candidate is taken").into(),
+                })
+            }
+        };
+
+        let row = ArticleRow {
+            id: Uuid::new_v4(),
+            slug,
+            title: draft.title,
+            description: draft.description,
+            body: draft.body,
+            tag_list: draft.tag_list,
+            user_id: author.id,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let article = store.to_article(&row)?;
+        store.articles.push(row);
+        Ok(article)
+    }
+
+    async fn get_article_by_slug(
+        &self,
+        slug: &str,
+    ) -> Result<realworld_domain::Article, realworld_domain::GetArticleError> {
+        let store = self.read();
+        let row = store.article(slug).ok_or_else(|| {
+            realworld_domain::GetArticleError::ArticleNotFound {
+                slug: slug.to_string(),
+                source: not_found("Article"),
+            }
+        })?;
+        Ok(store.to_article(row)?)
+    }
+
+    async fn get_current_slug(
+        &self,
+        old_slug: &str,
+    ) -> Result<Option<String>, realworld_domain::DatabaseError> {
+        let store = self.read();
+        let article = store
+            .slug_history
+            .get(old_slug)
+            .and_then(|id| store.article_by_id(*id));
+        Ok(article.map(|a| a.slug.clone()))
+    }
+
+    async fn get_article_view(
+        &self,
+        viewer: &realworld_domain::User,
+        article: realworld_domain::Article,
+    ) -> Result<realworld_domain::ArticleView, realworld_domain::GetArticleError> {
+        Ok(self.read().article_view(viewer, article)?)
+    }
+
+    async fn get_articles_views(
+        &self,
+        viewer: &realworld_domain::User,
+        articles: Vec<realworld_domain::Article>,
+    ) -> Result<Vec<realworld_domain::ArticleView>, realworld_domain::DatabaseError> {
+        let store = self.read();
+        articles
+            .into_iter()
+            .map(|article| Ok(store.article_view(viewer, article)?))
+            .collect()
+    }
+
+    async fn find_articles(
+        &self,
+        query: realworld_domain::ArticleQuery,
+    ) -> Result<Page<realworld_domain::Article>, realworld_domain::DatabaseError> {
+        let store = self.read();
+        let mut articles = match store.filter_articles(&query) {
+            Some(articles) => articles,
+            None => return Ok(Page::new(vec![], 0)),
+        };
+        // Sorts are stable: ties stay sorted by recency
+        match query.sort {
+            ArticleSort::Recent => {}
+            ArticleSort::Oldest => articles.reverse(),
+            ArticleSort::MostFavorited => {
+                articles.sort_by_key(|a| Reverse(store.n_favorites(a.id)));
+            }
+            ArticleSort::MostCommented => {
+                articles.sort_by_key(|a| Reverse(store.n_comments(a.id)));
+            }
+            ArticleSort::Updated => articles.sort_by_key(|a| Reverse(a.updated_at)),
+        }
+
+        let total = articles.len() as u64;
+        let articles = articles
+            .into_iter()
+            .skip(query.offset.unwrap_or_default() as usize)
+            .take(query.limit.map_or(usize::MAX, |limit| limit as usize))
+            .map(|a| Ok(store.to_article(a)?))
+            .collect::<Result<_, realworld_domain::DatabaseError>>()?;
+        Ok(Page::new(articles, total))
+    }
+
+    async fn search_articles(
+        &self,
+        text: &realworld_domain::SearchText,
+        query: realworld_domain::ArticleQuery,
+    ) -> Result<Page<realworld_domain::SearchHit>, realworld_domain::DatabaseError> {
+        let store = self.read();
+        let terms = search_terms(&text.0);
+        let mut hits: Vec<(f32, &ArticleRow)> = match store.filter_articles(&query) {
+            Some(articles) => articles
+                .into_iter()
+                .filter_map(|a| search_rank(a, &terms).map(|rank| (rank, a)))
+                .collect(),
+            None => return Ok(Page::new(vec![], 0)),
+        };
+        // Stable: ties stay sorted by recency
+        hits.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        let total = hits.len() as u64;
+        let hits = hits
+            .into_iter()
+            .skip(query.offset.unwrap_or_default() as usize)
+            .take(query.limit.map_or(usize::MAX, |limit| limit as usize))
+            .map(|(_, a)| {
+                Ok(realworld_domain::SearchHit {
+                    article: store.to_article(a)?,
+                    snippet: snippet(&format!("{} {}", a.description, a.body), &terms),
+                })
+            })
+            .collect::<Result<_, realworld_domain::DatabaseError>>()?;
+        Ok(Page::new(hits, total))
+    }
+
+    async fn feed(
+        &self,
+        user: &realworld_domain::User,
+        query: realworld_domain::FeedQuery,
+    ) -> Result<FeedPage, realworld_domain::DatabaseError> {
+        let store = self.read();
+        let articles: Vec<&ArticleRow> = store
+            .most_recent_articles()
+            .filter(|a| store.is_following(user.id, a.user_id))
+            .collect();
+
+        let total = articles.len() as u64;
+        let views = articles
+            .into_iter()
+            .skip_while(|a| {
+                query.cursor.is_some_and(|cursor| {
+                    a.created_at > cursor.created_at
+                        || (a.created_at == cursor.created_at && a.id <= cursor.id)
+                })
+            })
+            .skip(query.offset as usize)
+            .take(query.limit as usize + 1)
+            .map(|a| {
+                let article = store.to_article(a)?;
+                Ok(store.article_view(user, article)?)
+            })
+            .collect::<Result<_, realworld_domain::DatabaseError>>()?;
+        Ok(FeedPage::new(views, query.limit, total))
+    }
+
+    async fn delete_article(
+        &self,
+        article: &realworld_domain::Article,
+    ) -> Result<(), realworld_domain::DatabaseError> {
+        let mut store = self.write();
+        if store.article_by_id(article.id).is_none() {
+            return Err(not_found("Article"));
+        }
+
+        store.articles.retain(|a| a.id != article.id);
+        store.favorites.retain(|(_, id)| *id != article.id);
+        store.comments.retain(|c| c.article_id != article.id);
+        store.slug_history.retain(|_, id| *id != article.id);
+        Ok(())
+    }
+
+    async fn comment_article(
+        &self,
+        user: &realworld_domain::User,
+        article: &realworld_domain::Article,
+        comment: realworld_domain::CommentContent,
+        parent_id: Option<u64>,
+    ) -> Result<realworld_domain::Comment, realworld_domain::DatabaseError> {
+        let mut store = self.write();
+        if store.article_by_id(article.id).is_none() {
+            return Err(not_found("Article"));
+        }
+        store.user(user.id)?;
+
+        store.last_comment_id += 1;
+        let now = Utc::now();
+        let row = CommentRow {
+            id: store.last_comment_id,
+            author_id: user.id,
+            article_id: article.id,
+            body: comment.0,
+            created_at: now,
+            updated_at: now,
+            edited_at: None,
+            parent_id,
+            deleted: false,
+        };
+        store.comments.push(row);
+        let comment = store.comments.last().unwrap();
+        Ok(store.to_comment(comment)?)
+    }
+
+    async fn get_comment(
+        &self,
+        article: &realworld_domain::Article,
+        comment_id: u64,
+    ) -> Result<realworld_domain::Comment, realworld_domain::ChangeCommentError> {
+        let store = self.read();
+        let comment = store
+            .comments
+            .iter()
+            .find(|c| c.id == comment_id && c.article_id == article.id)
+            .ok_or_else(|| realworld_domain::ChangeCommentError::CommentNotFound {
+                comment_id,
+                source: not_found("Comment"),
+            })?;
+
+        Ok(store.to_comment(comment)?)
+    }
+
+    async fn get_comments(
+        &self,
+        article: &realworld_domain::Article,
+        query: realworld_domain::CommentQuery,
+    ) -> Result<realworld_domain::CommentPage, realworld_domain::DatabaseError> {
+        let store = self.read();
+        let on_article = || store.comments.iter().filter(|c| c.article_id == article.id);
+        let mut top_level: Vec<&CommentRow> =
+            on_article().filter(|c| c.parent_id.is_none()).collect();
+        if query.order == realworld_domain::CommentOrder::Desc {
+            top_level.reverse();
+        }
+        let total = top_level.len() as u64;
+
+        let mut top_level = top_level
+            .into_iter()
+            .skip_while(|c| {
+                query.cursor.is_some_and(|cursor| match query.order {
+                    realworld_domain::CommentOrder::Asc => c.id <= cursor,
+                    realworld_domain::CommentOrder::Desc => c.id >= cursor,
+                })
+            })
+            .skip(query.offset as usize)
+            .take(query.limit.map_or(usize::MAX, |limit| limit as usize + 1))
+            .map(|c| store.to_comment(c))
+            .collect::<Result<Vec<_>, _>>()?;
+        let next_cursor = query.next_cursor(&mut top_level);
+
+        // Replies are posted after their parent: one pass finds them all, however deep,
+        // mapping each of them to its top-level comment
+        let mut roots: HashMap<u64, u64> = top_level.iter().map(|c| (c.id, c.id)).collect();
+        let mut replies_counts: HashMap<u64, u64> = HashMap::new();
+        let mut comments = top_level;
+        for reply in on_article() {
+            let root = match reply.parent_id.and_then(|id| roots.get(&id)) {
+                Some(root) => *root,
+                None => continue,
+            };
+            roots.insert(reply.id, root);
+            let count = replies_counts.entry(root).or_default();
+            *count += 1;
+            if query.max_replies.is_none_or(|max| *count <= max) {
+                comments.push(store.to_comment(reply)?);
+            }
+        }
+        Ok(realworld_domain::CommentPage {
+            comments: Page::new(comments, total),
+            next_cursor,
+            replies_counts,
+        })
+    }
+
+    async fn get_comments_views(
+        &self,
+        viewer: &realworld_domain::User,
+        comments: Vec<realworld_domain::Comment>,
+    ) -> Result<Vec<realworld_domain::CommentView>, realworld_domain::DatabaseError> {
+        let store = self.read();
+        comments
+            .into_iter()
+            .map(|comment| {
+                let author = store.profile_view(viewer, &comment.author.username)?;
+                Ok(comment.with_author(author))
+            })
+            .collect()
+    }
+
+    async fn delete_comment(
+        &self,
+        comment_id: u64,
+    ) -> Result<(), realworld_domain::ChangeCommentError> {
+        let mut store = self.write();
+        let mut parent_id = store
+            .comments
+            .iter()
+            .find(|c| c.id == comment_id)
+            .and_then(|c| c.parent_id);
+        store.delete_comment(comment_id);
+        // Tombstones left without replies go too, up the thread
+        while let Some(id) = parent_id {
+            let parent = store.comments.iter().find(|c| c.id == id);
+            match parent {
+                Some(parent) if parent.deleted && !store.has_replies(id) => {
+                    parent_id = parent.parent_id;
+                    store.delete_comment(id);
+                }
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    async fn has_replies(&self, comment_id: u64) -> Result<bool, realworld_domain::DatabaseError> {
+        Ok(self.read().has_replies(comment_id))
+    }
+
+    async fn tombstone_comment(
+        &self,
+        comment_id: u64,
+    ) -> Result<(), realworld_domain::ChangeCommentError> {
+        let mut store = self.write();
+        let comment = store
+            .comments
+            .iter_mut()
+            .find(|c| c.id == comment_id)
+            .ok_or_else(|| realworld_domain::ChangeCommentError::CommentNotFound {
+                comment_id,
+                source: not_found("Comment"),
+            })?;
+        comment.body = String::new();
+        comment.deleted = true;
+        Ok(())
+    }
+
+    async fn update_comment(
+        &self,
+        comment_id: u64,
+        content: realworld_domain::CommentContent,
+    ) -> Result<realworld_domain::Comment, realworld_domain::ChangeCommentError> {
+        let mut store = self.write();
+        let comment = store
+            .comments
+            .iter_mut()
+            .find(|c| c.id == comment_id)
+            .ok_or_else(|| realworld_domain::ChangeCommentError::CommentNotFound {
+                comment_id,
+                source: not_found("Comment"),
+            })?;
+        let now = Utc::now();
+        comment.body = content.0;
+        comment.updated_at = now;
+        comment.edited_at = Some(now);
+
+        let comment = comment.clone();
+        Ok(store.to_comment(&comment)?)
+    }
+
+    async fn update_article(
+        &self,
+        article: realworld_domain::Article,
+        update: realworld_domain::ArticleUpdate,
+    ) -> Result<realworld_domain::Article, realworld_domain::DatabaseError> {
+        let mut store = self.write();
+        if store.article_by_id(article.id).is_none() {
+            return Err(not_found("Article"));
+        }
+        if let Some(new_slug) = update.new_slug(&article) {
+            let new_slug = slug_candidates(new_slug)
+                .find(|s| store.slug_is_available_to(s, article.id))
+                .ok_or_else(|| anyhow::anyhow!("Every slug candidate is taken"))?;
+            if new_slug != article.slug {
+                store.rename_article(article.id, &new_slug);
+            }
+        }
+        let row = store
+            .article_mut(article.id)
+            .ok_or_else(|| not_found("Article"))?;
+
+        if let Some(title) = update.title {
+            row.title = title;
+        }
+        if let Some(description) = update.description {
+            row.description = description;
+        }
+        if let Some(body) = update.body {
+            row.body = body;
+        }
+        if let Some(tag_list) = &update.tag_list {
+            row.tag_list = tag_list.apply(&article.content.tag_list);
+        }
+        row.updated_at = Utc::now();
+
+        let row = row.clone();
+        Ok(store.to_article(&row)?)
+    }
+
+    async fn favorite(
+        &self,
+        article: &realworld_domain::Article,
+        user: &realworld_domain::User,
+    ) -> Result<realworld_domain::FavoriteOutcome, realworld_domain::DatabaseError> {
+        let mut store = self.write();
+        if store.article_by_id(article.id).is_none() {
+            return Err(not_found("Article"));
+        }
+        store.user(user.id)?;
+
+        if store.favorites.insert((user.id, article.id)) {
+            Ok(FavoriteOutcome::NewFavorite)
+        } else {
+            Ok(FavoriteOutcome::AlreadyAFavorite)
+        }
+    }
+
+    async fn unfavorite(
+        &self,
+        article: &realworld_domain::Article,
+        user: &realworld_domain::User,
+    ) -> Result<realworld_domain::UnfavoriteOutcome, realworld_domain::DatabaseError> {
+        if self.write().favorites.remove(&(user.id, article.id)) {
+            Ok(UnfavoriteOutcome::WasAFavorite)
+        } else {
+            Ok(UnfavoriteOutcome::WasNotAFavorite)
+        }
+    }
+
+    async fn sign_up(
+        &self,
+        sign_up: realworld_domain::SignUp,
+    ) -> Result<realworld_domain::User, realworld_domain::SignUpError> {
+        let mut store = self.write();
+        let id = Uuid::new_v4();
+        match store.user_conflict(id, &sign_up.username, &sign_up.email) {
+            Some(UserConflict::Username) => {
+                return Err(realworld_domain::SignUpError::UsernameTaken {
+                    username: sign_up.username,
+                })
+            }
+            Some(UserConflict::Email) => {
+                return Err(realworld_domain::SignUpError::EmailTaken {
+                    email: sign_up.email,
+                })
+            }
+            None => {}
+        }
+
+        let row = UserRow {
+            id,
+            username: sign_up.username,
+            email: sign_up.email,
+            password: sign_up.password.hash().to_string(),
+            bio: None,
+            image: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let user = realworld_domain::User::from(&row);
+        store.users.insert(id, row);
+        // Users always self-follow
+        store.followers.insert((id, id));
+        Ok(user)
+    }
+
+    async fn update_user(
+        &self,
+        user: realworld_domain::User,
+        realworld_domain::UserUpdate {
+            email,
+            username,
+            password,
+            image,
+            bio,
+        }: realworld_domain::UserUpdate,
+    ) -> Result<realworld_domain::User, realworld_domain::UpdateUserError> {
+        let mut store = self.write();
+        let mut row = store.user(user.id)?.clone();
+
+        if let Some(email) = email {
+            row.email = email;
+        }
+        if let Some(username) = username {
+            row.username = username;
+        }
+        if let Some(password) = password {
+            row.password = password.hash().to_string();
+        }
+
+        row.bio = bio;
+        row.image = image;
+
+        match store.user_conflict(row.id, &row.username, &row.email) {
+            Some(UserConflict::Username) => {
+                return Err(realworld_domain::UpdateUserError::UsernameTaken {
+                    username: row.username,
+                })
+            }
+            Some(UserConflict::Email) => {
+                return Err(realworld_domain::UpdateUserError::EmailTaken { email: row.email })
+            }
+            None => {}
+        }
+        let user = realworld_domain::User::from(&row);
+        store.users.insert(row.id, row);
+        Ok(user)
+    }
+
+    async fn get_user_by_id(
+        &self,
+        user_id: Uuid,
+    ) -> Result<realworld_domain::User, realworld_domain::GetUserError> {
+        self.read().user(user_id).map(Into::into)
+    }
+
+    async fn get_user_by_email_and_password(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<realworld_domain::User, realworld_domain::LoginError> {
+        let store = self.read();
+        let user = store
+            .users
+            .values()
+            .find(|u| u.email == email)
+            .ok_or_else(|| realworld_domain::Password::reject_unknown_user(password))?;
+        realworld_domain::Password::from_hash(user.password.to_owned()).check(password)?;
+        Ok(user.into())
+    }
+
+    async fn get_profile(
+        &self,
+        username: &str,
+    ) -> Result<realworld_domain::Profile, realworld_domain::GetUserError> {
+        self.read().user_by_username(username).map(Into::into)
+    }
+
+    async fn get_profile_view(
+        &self,
+        viewer: &realworld_domain::User,
+        username: &str,
+    ) -> Result<realworld_domain::ProfileView, realworld_domain::GetUserError> {
+        self.read().profile_view(viewer, username)
+    }
+
+    async fn follow(
+        &self,
+        follower: &realworld_domain::User,
+        to_be_followed: &realworld_domain::Profile,
+    ) -> Result<(), realworld_domain::DatabaseError> {
+        let mut store = self.write();
+        let followed_id = store.user_by_username(&to_be_followed.username)?.id;
+        store.user(follower.id)?;
+
+        store.followers.insert((follower.id, followed_id));
+        Ok(())
+    }
+
+    async fn unfollow(
+        &self,
+        follower: &realworld_domain::User,
+        to_be_unfollowed: &realworld_domain::Profile,
+    ) -> Result<(), realworld_domain::DatabaseError> {
+        let mut store = self.write();
+        let followed_id = store.user_by_username(&to_be_unfollowed.username)?.id;
+
+        store.followers.remove(&(follower.id, followed_id));
+        Ok(())
+    }
+
+    async fn get_tags(
+        &self,
+        limit: Option<u64>,
+    ) -> Result<Vec<realworld_domain::TagCount>, realworld_domain::DatabaseError> {
+        let mut counts: HashMap<&str, u64> = HashMap::new();
+        let store = self.read();
+        for tag in store.articles.iter().flat_map(|a| &a.tag_list) {
+            *counts.entry(tag).or_default() += 1;
+        }
+        let mut tags: Vec<realworld_domain::TagCount> = counts
+            .into_iter()
+            .map(|(tag, count)| realworld_domain::TagCount {
+                tag: tag.to_string(),
+                count,
+            })
+            .collect();
+        tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+        tags.truncate(limit.map_or(usize::MAX, |l| l as usize));
+        Ok(tags)
+    }
+
+    async fn create_session(
+        &self,
+        user: &realworld_domain::User,
+        refresh_token: &realworld_domain::RefreshToken,
+        expires_at: DateTime<Utc>,
+    ) -> Result<realworld_domain::Session, realworld_domain::DatabaseError> {
+        let mut store = self.write();
+        store.user(user.id)?;
+        let row = SessionRow {
+            id: Uuid::new_v4(),
+            user_id: user.id,
+            refresh_token_hash: refresh_token.hash(),
+            created_at: Utc::now(),
+            expires_at,
+            revoked_at: None,
+        };
+        let session = (&row).into();
+        store.sessions.insert(row.id, row);
+        Ok(session)
+    }
+
+    async fn get_session(
+        &self,
+        session_id: Uuid,
+    ) -> Result<realworld_domain::Session, realworld_domain::GetSessionError> {
+        self.read()
+            .sessions
+            .get(&session_id)
+            .map(Into::into)
+            .ok_or_else(|| realworld_domain::GetSessionError::NotFound {
+                session_id,
+                source: not_found("Session"),
+            })
+    }
+
+    async fn get_session_by_refresh_token(
+        &self,
+        refresh_token: &realworld_domain::RefreshToken,
+    ) -> Result<realworld_domain::Session, realworld_domain::GetSessionError> {
+        let hash = refresh_token.hash();
+        self.read()
+            .sessions
+            .values()
+            .find(|s| s.refresh_token_hash == hash)
+            .map(Into::into)
+            .ok_or_else(
+                || realworld_domain::GetSessionError::NotFoundByRefreshToken {
+                    source: not_found("Session"),
+                },
+            )
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        session: &realworld_domain::Session,
+        current: &realworld_domain::RefreshToken,
+        refresh_token: &realworld_domain::RefreshToken,
+        expires_at: DateTime<Utc>,
+    ) -> Result<realworld_domain::Session, realworld_domain::RefreshSessionError> {
+        let mut store = self.write();
+        let row = store
+            .sessions
+            .get_mut(&session.id)
+            .filter(|row| row.refresh_token_hash == current.hash() && row.revoked_at.is_none())
+            .ok_or(realworld_domain::RefreshSessionError::InvalidRefreshToken)?;
+        row.refresh_token_hash = refresh_token.hash();
+        row.expires_at = expires_at;
+        Ok((&*row).into())
+    }
+
+    async fn revoke_session(
+        &self,
+        session_id: Uuid,
+    ) -> Result<(), realworld_domain::DatabaseError> {
+        if let Some(row) = self.write().sessions.get_mut(&session_id) {
+            row.revoked_at.get_or_insert_with(Utc::now);
+        }
+        Ok(())
+    }
+
+    async fn revoke_sessions(
+        &self,
+        user: &realworld_domain::User,
+    ) -> Result<(), realworld_domain::DatabaseError> {
+        let mut store = self.write();
+        for row in store.sessions.values_mut().filter(|s| s.user_id == user.id) {
+            row.revoked_at.get_or_insert_with(Utc::now);
+        }
+        Ok(())
+    }
+}
+
+/// The lowercased words of a search: unlike Postgres, words are neither stemmed nor
+/// combined with operators.
+fn search_terms(text: &str) -> Vec<String> {
+    words(text).map(|w| w.to_lowercase()).collect()
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+}
+
+/// How relevant `article` is to a search for all of `terms`, weighing matches in the title
+/// and the description more, or `None` if some term is missing.
+fn search_rank(article: &ArticleRow, terms: &[String]) -> Option<f32> {
+    let fields = [
+        (&article.title, 1.0),
+        (&article.description, 0.4),
+        (&article.body, 0.2),
+    ];
+    let mut rank = 0.0;
+    for term in terms {
+        let mut matches = 0.0;
+        for (field, weight) in fields {
+            let n = words(field).filter(|w| w.to_lowercase() == *term).count();
+            matches += n as f32 * weight;
+        }
+        if matches == 0.0 {
+            return None;
+        }
+        rank += matches;
+    }
+    Some(rank)
+}
+
+/// About 30 words of `text` around the first matching word, HTML-escaped, with matches wrapped
+/// in `<mark>`.
+fn snippet(text: &str, terms: &[String]) -> String {
+    let is_match = |word: &str| {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        terms.contains(&word.to_lowercase())
+    };
+    let text: Vec<&str> = text.split_whitespace().collect();
+    let first = text.iter().position(|w| is_match(w)).unwrap_or_default();
+    text.iter()
+        .skip(first.saturating_sub(5))
+        .take(30)
+        .map(|w| match is_match(w) {
+            true => format!("<mark>{}</mark>", escape_html(w)),
+            false => escape_html(w),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
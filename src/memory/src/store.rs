@@ -0,0 +1,339 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use realworld_domain::{ArticleQuery, DatabaseError, GetUserError, Profile, TagMatch, User};
+use uuid::Uuid;
+
+/// In-memory counterpart of the `users` table.
+#[derive(Clone, Debug)]
+pub struct UserRow {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    pub bio: Option<String>,
+    pub image: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// In-memory counterpart of the `articles` table.
+#[derive(Clone, Debug)]
+pub struct ArticleRow {
+    pub id: Uuid,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub body: String,
+    pub tag_list: Vec<String>,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// In-memory counterpart of the `comments` table.
+#[derive(Clone, Debug)]
+pub struct CommentRow {
+    pub id: u64,
+    pub author_id: Uuid,
+    pub article_id: Uuid,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub parent_id: Option<u64>,
+    pub deleted: bool,
+}
+
+/// In-memory counterpart of the `sessions` table.
+#[derive(Clone, Debug)]
+pub struct SessionRow {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub refresh_token_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// The unique column of `users` a new or updated user collides with.
+pub enum UserConflict {
+    Username,
+    Email,
+}
+
+/// All the rows held by the in-memory repository.
+///
+/// Articles and comments are kept in insertion order.
+#[derive(Default)]
+pub struct Store {
+    pub users: HashMap<Uuid, UserRow>,
+    pub articles: Vec<ArticleRow>,
+    pub comments: Vec<CommentRow>,
+    /// (user id, article id)
+    pub favorites: HashSet<(Uuid, Uuid)>,
+    /// (follower id, followed id)
+    pub followers: HashSet<(Uuid, Uuid)>,
+    pub sessions: HashMap<Uuid, SessionRow>,
+    /// (old slug, article id) of renamed articles
+    pub slug_history: HashMap<String, Uuid>,
+    pub last_comment_id: u64,
+}
+
+impl Store {
+    pub fn user(&self, user_id: Uuid) -> Result<&UserRow, GetUserError> {
+        self.users
+            .get(&user_id)
+            .ok_or_else(|| GetUserError::NotFound {
+                user_id,
+                source: not_found("User"),
+            })
+    }
+
+    pub fn user_by_username(&self, username: &str) -> Result<&UserRow, GetUserError> {
+        self.users
+            .values()
+            .find(|u| u.username == username)
+            .ok_or_else(|| GetUserError::NotFoundByUsername {
+                username: username.to_string(),
+                source: not_found("User"),
+            })
+    }
+
+    pub fn article(&self, slug: &str) -> Option<&ArticleRow> {
+        self.articles.iter().find(|a| a.slug == slug)
+    }
+
+    pub fn article_by_id(&self, id: Uuid) -> Option<&ArticleRow> {
+        self.articles.iter().find(|a| a.id == id)
+    }
+
+    /// Articles sorted from the most recent to the oldest, with the id as tie-breaker.
+    pub fn most_recent_articles(&self) -> impl Iterator<Item = &ArticleRow> {
+        let mut articles: Vec<&ArticleRow> = self.articles.iter().collect();
+        articles.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        articles.into_iter()
+    }
+
+    /// Articles matching the filters of `query`, the most recent first, or `None` if no
+    /// article can match.
+    pub fn filter_articles(&self, query: &ArticleQuery) -> Option<Vec<&ArticleRow>> {
+        let author_id = match &query.author {
+            Some(username) => Some(self.user_by_username(username).ok()?.id),
+            None => None,
+        };
+        let fan_id = match &query.favorited {
+            Some(username) => Some(self.user_by_username(username).ok()?.id),
+            None => None,
+        };
+        let tag_filter = query.tag_filter();
+        let articles = self
+            .most_recent_articles()
+            .filter(|a| author_id.is_none_or(|id| a.user_id == id))
+            .filter(|a| fan_id.is_none_or(|id| self.is_favorite(a.id, id)))
+            .filter(|a| {
+                let mut tags = tag_filter.iter();
+                match query.tag_match {
+                    _ if tag_filter.is_empty() => true,
+                    TagMatch::Any => tags.any(|t| a.tag_list.contains(t)),
+                    TagMatch::All => tags.all(|t| a.tag_list.contains(t)),
+                }
+            })
+            .collect();
+        Some(articles)
+    }
+
+    pub fn article_mut(&mut self, id: Uuid) -> Option<&mut ArticleRow> {
+        self.articles.iter_mut().find(|a| a.id == id)
+    }
+
+    /// Old slugs stay reserved for the article they used to point to.
+    pub fn slug_is_taken(&self, slug: &str) -> bool {
+        self.article(slug).is_some() || self.slug_history.contains_key(slug)
+    }
+
+    /// Whether the article `article_id` can move to `slug`: going back to one of
+    /// its own old slugs is fine.
+    pub fn slug_is_available_to(&self, slug: &str, article_id: Uuid) -> bool {
+        match self.article(slug) {
+            Some(a) => a.id == article_id,
+            None => self
+                .slug_history
+                .get(slug)
+                .is_none_or(|id| *id == article_id),
+        }
+    }
+
+    /// Moves an article to `new_slug`, keeping its current slug around.
+    pub fn rename_article(&mut self, article_id: Uuid, new_slug: &str) {
+        if let Some(row) = self.article_mut(article_id) {
+            let old_slug = std::mem::replace(&mut row.slug, new_slug.to_string());
+            self.slug_history.remove(new_slug);
+            self.slug_history.insert(old_slug, article_id);
+        }
+    }
+
+    /// Mirrors the unique constraints on `users.username` and `users.email`.
+    pub fn user_conflict(
+        &self,
+        user_id: Uuid,
+        username: &str,
+        email: &str,
+    ) -> Option<UserConflict> {
+        let others = || self.users.values().filter(|u| u.id != user_id);
+        if others().any(|u| u.username == username) {
+            Some(UserConflict::Username)
+        } else if others().any(|u| u.email == email) {
+            Some(UserConflict::Email)
+        } else {
+            None
+        }
+    }
+
+    pub fn n_favorites(&self, article_id: Uuid) -> u64 {
+        self.favorites
+            .iter()
+            .filter(|(_, id)| *id == article_id)
+            .count() as u64
+    }
+
+    pub fn n_comments(&self, article_id: Uuid) -> u64 {
+        self.comments
+            .iter()
+            .filter(|c| c.article_id == article_id && !c.deleted)
+            .count() as u64
+    }
+
+    pub fn is_favorite(&self, article_id: Uuid, user_id: Uuid) -> bool {
+        self.favorites.contains(&(user_id, article_id))
+    }
+
+    pub fn has_replies(&self, comment_id: u64) -> bool {
+        self.comments
+            .iter()
+            .any(|c| c.parent_id == Some(comment_id))
+    }
+
+    /// Like the `ON DELETE SET NULL` of `comments.parent_id`, replies lose their parent.
+    pub fn delete_comment(&mut self, comment_id: u64) {
+        self.comments.retain(|c| c.id != comment_id);
+        for reply in self.comments.iter_mut() {
+            if reply.parent_id == Some(comment_id) {
+                reply.parent_id = None;
+            }
+        }
+    }
+
+    pub fn is_following(&self, follower_id: Uuid, followed_id: Uuid) -> bool {
+        self.followers.contains(&(follower_id, followed_id))
+    }
+
+    pub fn to_article(&self, a: &ArticleRow) -> Result<realworld_domain::Article, GetUserError> {
+        let author = self.user(a.user_id)?;
+        let mut tags = a.tag_list.clone();
+        tags.sort();
+        Ok(realworld_domain::Article {
+            id: a.id,
+            content: realworld_domain::ArticleContent {
+                title: a.title.clone(),
+                description: a.description.clone(),
+                body: a.body.clone(),
+                tag_list: tags,
+            },
+            slug: a.slug.clone(),
+            author: author.into(),
+            metadata: realworld_domain::ArticleMetadata {
+                created_at: a.created_at,
+                updated_at: a.updated_at,
+            },
+            favorites_count: self.n_favorites(a.id),
+        })
+    }
+
+    pub fn to_comment(&self, c: &CommentRow) -> Result<realworld_domain::Comment, GetUserError> {
+        let author = self.user(c.author_id)?;
+        Ok(realworld_domain::Comment {
+            id: c.id,
+            article_id: c.article_id,
+            parent_id: c.parent_id,
+            author: author.into(),
+            body: c.body.clone(),
+            created_at: c.created_at,
+            updated_at: c.updated_at,
+            edited_at: c.edited_at,
+            deleted: c.deleted,
+        })
+    }
+
+    pub fn profile_view(
+        &self,
+        viewer: &User,
+        username: &str,
+    ) -> Result<realworld_domain::ProfileView, GetUserError> {
+        let viewed = self.user_by_username(username)?;
+        Ok(realworld_domain::ProfileView {
+            profile: viewed.into(),
+            following: self.is_following(viewer.id, viewed.id),
+            viewer: viewer.id,
+        })
+    }
+
+    pub fn article_view(
+        &self,
+        viewer: &User,
+        article: realworld_domain::Article,
+    ) -> Result<realworld_domain::ArticleView, GetUserError> {
+        let author = self.profile_view(viewer, &article.author.username)?;
+        let favorited = self.is_favorite(article.id, viewer.id);
+        Ok(realworld_domain::ArticleView {
+            id: article.id,
+            content: article.content,
+            slug: article.slug,
+            author,
+            metadata: article.metadata,
+            favorited,
+            favorites_count: article.favorites_count,
+            viewer: viewer.id,
+        })
+    }
+}
+
+impl From<&UserRow> for User {
+    fn from(u: &UserRow) -> Self {
+        User {
+            id: u.id,
+            email: u.email.clone(),
+            profile: u.into(),
+        }
+    }
+}
+
+impl From<&UserRow> for Profile {
+    fn from(u: &UserRow) -> Self {
+        Profile {
+            username: u.username.clone(),
+            bio: u.bio.clone(),
+            image: u.image.clone(),
+        }
+    }
+}
+
+impl From<&SessionRow> for realworld_domain::Session {
+    fn from(s: &SessionRow) -> Self {
+        realworld_domain::Session {
+            id: s.id,
+            user_id: s.user_id,
+            created_at: s.created_at,
+            expires_at: s.expires_at,
+            revoked_at: s.revoked_at,
+        }
+    }
+}
+
+pub fn not_found(entity: &str) -> DatabaseError {
+    anyhow::anyhow!("{} not found", entity).into()
+}
@@ -0,0 +1,8 @@
+use realworld_memory::Repository;
+use realworld_tests::repository_conformance;
+
+async fn repository(_name: &str) -> Repository {
+    Repository::new()
+}
+
+repository_conformance!(repository);
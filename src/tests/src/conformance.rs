@@ -0,0 +1,1682 @@
+//! A test suite that every implementation of `realworld_domain::repositories::Repository`
+//! has to pass.
+//!
+//! Each check is a plain async function, generic over the repository.
+//! Use [`repository_conformance!`](crate::repository_conformance) to turn all of them
+//! into `#[tokio::test]`s for a given backend.
+use chrono::{Duration, Utc};
+use fake::fake;
+use futures::future::join_all;
+use realworld_domain::repositories::Repository;
+use realworld_domain::{
+    Article, ArticleContent, ArticleQuery, ArticleSort, ArticleUpdate, ChangeCommentError,
+    CommentContent, CommentOrder, CommentQuery, FavoriteOutcome, FeedCursor, FeedQuery,
+    GetArticleError, GetSessionError, GetUserError, LoginError, Password, RefreshSessionError,
+    RefreshToken, SearchText, Session, SignUp, SignUpError, TagCount, TagListUpdate, TagMatch,
+    UnfavoriteOutcome, UpdateUserError, User, UserUpdate,
+};
+use std::collections::HashMap;
+
+/// Generates a `#[tokio::test]` for each check of the conformance suite.
+///
+/// `$repository` must be an async function taking the (unique) name of the test
+/// and returning a fresh, empty repository.
+#[macro_export]
+macro_rules! repository_conformance {
+    ($repository:path) => {
+        $crate::repository_conformance!(
+            @checks $repository;
+            sign_up_and_get_user,
+            users_follow_themselves,
+            usernames_and_emails_are_unique,
+            login_with_email_and_password,
+            update_user,
+            get_profile_and_profile_view,
+            follow_and_unfollow,
+            publish_and_get_article,
+            slugs_are_unique,
+            missing_article,
+            update_article,
+            update_article_tags,
+            rename_article,
+            delete_article,
+            find_articles,
+            paginate_articles,
+            find_favorited_articles,
+            find_articles_by_tags,
+            find_articles_by_unnormalized_tags,
+            favorite_and_unfavorite,
+            favorite_a_missing_article,
+            concurrent_favorites_and_follows,
+            sort_articles_by_favorites,
+            sort_articles,
+            search_articles,
+            articles_views,
+            feed,
+            feed_cursor,
+            comments,
+            comments_views,
+            edit_comment,
+            comment_replies,
+            delete_empty_tombstones,
+            paginate_comments,
+            tags,
+            sessions,
+            refresh_sessions,
+        );
+    };
+    (@checks $repository:path; $($check:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $check() {
+                let repository = $repository(concat!("conformance_", stringify!($check))).await;
+                $crate::conformance::$check(&repository).await;
+            }
+        )*
+    };
+}
+
+pub fn article_content() -> ArticleContent {
+    ArticleContent {
+        title: fake!(Lorem.sentence(4, 10)).to_string(),
+        description: fake!(Lorem.paragraph(3, 10)),
+        body: fake!(Lorem.paragraph(10, 5)),
+        tag_list: vec![fake!(Lorem.word).to_string()],
+    }
+}
+
+pub fn new_user() -> (SignUp, String) {
+    let password = fake!(Lorem.word).to_string();
+    let sign_up = SignUp {
+        username: fake!(Internet.user_name).to_string(),
+        email: fake!(Internet.free_email).to_string(),
+        password: Password::from_clear_text(password.clone()).expect("Failed to hash password"),
+    };
+    (sign_up, password)
+}
+
+async fn create_user(repository: &impl Repository) -> (User, String) {
+    let (sign_up, password) = new_user();
+    let user = repository
+        .sign_up(sign_up)
+        .await
+        .expect("Failed to create user");
+    (user, password)
+}
+
+async fn create_article(repository: &impl Repository, author: &User) -> Article {
+    repository
+        .publish_article(article_content(), author)
+        .await
+        .expect("Failed to publish article")
+}
+
+pub async fn sign_up_and_get_user(repository: &impl Repository) {
+    let (sign_up, _) = new_user();
+    let user = repository.sign_up(sign_up.clone()).await.unwrap();
+
+    assert_eq!(user.profile.username, sign_up.username);
+    assert_eq!(user.email, sign_up.email);
+    assert_eq!(user.profile.bio, None);
+    assert_eq!(user.profile.image, None);
+    assert_eq!(repository.get_user_by_id(user.id).await.unwrap(), user);
+}
+
+pub async fn users_follow_themselves(repository: &impl Repository) {
+    let user = create_user(repository).await.0;
+
+    let view = repository
+        .get_profile_view(&user, &user.profile.username)
+        .await
+        .unwrap();
+    assert!(view.following);
+}
+
+pub async fn usernames_and_emails_are_unique(repository: &impl Repository) {
+    let (sign_up, _) = new_user();
+    repository.sign_up(sign_up.clone()).await.unwrap();
+
+    let same_username = SignUp {
+        email: format!("other.{}", sign_up.email),
+        ..sign_up.clone()
+    };
+    let result = repository.sign_up(same_username).await;
+    assert!(
+        matches!(&result, Err(SignUpError::UsernameTaken { username }) if username == &sign_up.username)
+    );
+
+    let same_email = SignUp {
+        username: format!("other_{}", sign_up.username),
+        ..sign_up.clone()
+    };
+    let result = repository.sign_up(same_email).await;
+    assert!(matches!(&result, Err(SignUpError::EmailTaken { email }) if email == &sign_up.email));
+
+    let user = create_user(repository).await.0;
+    let update = |username: Option<String>, email: Option<String>| UserUpdate {
+        email,
+        username,
+        password: None,
+        image: None,
+        bio: None,
+    };
+    let result = repository
+        .update_user(user.clone(), update(Some(sign_up.username.clone()), None))
+        .await;
+    assert!(matches!(result, Err(UpdateUserError::UsernameTaken { .. })));
+    let result = repository
+        .update_user(user.clone(), update(None, Some(sign_up.email.clone())))
+        .await;
+    assert!(matches!(result, Err(UpdateUserError::EmailTaken { .. })));
+    assert_eq!(repository.get_user_by_id(user.id).await.unwrap(), user);
+
+    // Keeping one's own username and email is not a conflict
+    let result = repository
+        .update_user(
+            user.clone(),
+            update(
+                Some(user.profile.username.clone()),
+                Some(user.email.clone()),
+            ),
+        )
+        .await;
+    assert!(result.is_ok());
+}
+
+pub async fn login_with_email_and_password(repository: &impl Repository) {
+    let (user, password) = create_user(repository).await;
+
+    let logged_in = repository
+        .get_user_by_email_and_password(&user.email, &password)
+        .await
+        .unwrap();
+    assert_eq!(logged_in, user);
+
+    let wrong_password = format!("not-{}", password);
+    let result = repository
+        .get_user_by_email_and_password(&user.email, &wrong_password)
+        .await;
+    assert!(matches!(result, Err(LoginError::InvalidCredentials)));
+
+    let result = repository
+        .get_user_by_email_and_password("nobody@example.com", &password)
+        .await;
+    assert!(matches!(result, Err(LoginError::InvalidCredentials)));
+}
+
+pub async fn update_user(repository: &impl Repository) {
+    let user = create_user(repository).await.0;
+    let new_password = fake!(Lorem.word).to_string();
+
+    let update = UserUpdate {
+        email: Some(fake!(Internet.free_email).to_string()),
+        username: None,
+        password: Some(Password::from_clear_text(new_password.clone()).unwrap()),
+        image: Some(fake!(Internet.domain_suffix).to_string()),
+        bio: Some(fake!(Lorem.paragraph(3, 5)).to_string()),
+    };
+    let updated = repository
+        .update_user(user.clone(), update.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(updated.id, user.id);
+    assert_eq!(Some(updated.email.clone()), update.email);
+    assert_eq!(updated.profile.username, user.profile.username);
+    assert_eq!(updated.profile.bio, update.bio);
+    assert_eq!(updated.profile.image, update.image);
+    assert_eq!(repository.get_user_by_id(user.id).await.unwrap(), updated);
+    assert!(repository
+        .get_user_by_email_and_password(&updated.email, &new_password)
+        .await
+        .is_ok());
+
+    let missing = User {
+        id: uuid::Uuid::new_v4(),
+        ..updated
+    };
+    let result = repository.update_user(missing, update).await;
+    assert!(matches!(result, Err(UpdateUserError::UserNotFound { .. })));
+}
+
+pub async fn get_profile_and_profile_view(repository: &impl Repository) {
+    let viewer = create_user(repository).await.0;
+    let viewed = create_user(repository).await.0;
+
+    let profile = repository
+        .get_profile(&viewed.profile.username)
+        .await
+        .unwrap();
+    assert_eq!(profile, viewed.profile);
+
+    let view = repository
+        .get_profile_view(&viewer, &viewed.profile.username)
+        .await
+        .unwrap();
+    assert_eq!(view.profile, viewed.profile);
+    assert_eq!(view.viewer, viewer.id);
+    assert!(!view.following);
+
+    let result = repository.get_profile("nobody").await;
+    assert!(matches!(
+        result,
+        Err(GetUserError::NotFoundByUsername { .. })
+    ));
+    let result = repository.get_profile_view(&viewer, "nobody").await;
+    assert!(matches!(
+        result,
+        Err(GetUserError::NotFoundByUsername { .. })
+    ));
+}
+
+pub async fn follow_and_unfollow(repository: &impl Repository) {
+    let follower = create_user(repository).await.0;
+    let followed = create_user(repository).await.0;
+    let is_following = || async {
+        repository
+            .get_profile_view(&follower, &followed.profile.username)
+            .await
+            .unwrap()
+            .following
+    };
+
+    // Following twice is not an error
+    repository
+        .follow(&follower, &followed.profile)
+        .await
+        .unwrap();
+    repository
+        .follow(&follower, &followed.profile)
+        .await
+        .unwrap();
+    assert!(is_following().await);
+
+    // The relationship is not symmetric
+    let view = repository
+        .get_profile_view(&followed, &follower.profile.username)
+        .await
+        .unwrap();
+    assert!(!view.following);
+
+    repository
+        .unfollow(&follower, &followed.profile)
+        .await
+        .unwrap();
+    assert!(!is_following().await);
+    // Unfollowing twice is not an error
+    repository
+        .unfollow(&follower, &followed.profile)
+        .await
+        .unwrap();
+}
+
+pub async fn publish_and_get_article(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let mut draft = article_content();
+    draft.tag_list = vec!["zeta".to_string(), "alpha".to_string()];
+
+    let article = repository
+        .publish_article(draft.clone(), &author)
+        .await
+        .unwrap();
+    assert_eq!(article.slug, draft.slug());
+    assert_eq!(article.author, author.profile);
+    assert_eq!(article.content.title, draft.title);
+    assert_eq!(article.content.description, draft.description);
+    assert_eq!(article.content.body, draft.body);
+    // Tags are returned sorted
+    assert_eq!(article.content.tag_list, vec!["alpha", "zeta"]);
+    assert_eq!(article.favorites_count, 0);
+
+    let retrieved = repository.get_article_by_slug(&article.slug).await.unwrap();
+    assert_eq!(retrieved, article);
+}
+
+pub async fn slugs_are_unique(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let draft = article_content();
+
+    let first = repository
+        .publish_article(draft.clone(), &author)
+        .await
+        .unwrap();
+    // Same title, yet every article gets its own slug
+    let second = repository
+        .publish_article(draft.clone(), &author)
+        .await
+        .unwrap();
+    let third = repository.publish_article(draft, &author).await.unwrap();
+    assert_eq!(second.slug, format!("{}-2", first.slug));
+    assert_eq!(third.slug, format!("{}-3", first.slug));
+    assert_ne!(first.id, second.id);
+    for article in [first, second, third] {
+        let retrieved = repository.get_article_by_slug(&article.slug).await.unwrap();
+        assert_eq!(retrieved, article);
+    }
+}
+
+pub async fn missing_article(repository: &impl Repository) {
+    let result = repository.get_article_by_slug("missing").await;
+    assert!(matches!(
+        result,
+        Err(GetArticleError::ArticleNotFound { .. })
+    ));
+}
+
+pub async fn update_article(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let article = create_article(repository, &author).await;
+
+    let update = ArticleUpdate {
+        title: Some(fake!(Lorem.sentence(4, 10)).to_string()),
+        description: None,
+        body: Some(fake!(Lorem.paragraph(10, 5)).to_string()),
+        tag_list: None,
+        slug_follows_title: false,
+    };
+    let updated = repository
+        .update_article(article.clone(), update.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(Some(updated.content.title.clone()), update.title);
+    assert_eq!(updated.content.description, article.content.description);
+    assert_eq!(Some(updated.content.body.clone()), update.body);
+    assert_eq!(updated.slug, article.slug);
+    assert_eq!(updated.content.tag_list, article.content.tag_list);
+    assert!(updated.metadata.updated_at > article.metadata.updated_at);
+    assert_eq!(
+        repository.get_article_by_slug(&article.slug).await.unwrap(),
+        updated
+    );
+}
+
+pub async fn update_article_tags(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let draft = ArticleContent {
+        tag_list: vec!["dragons".to_string(), "training".to_string()],
+        ..article_content()
+    };
+    let article = repository.publish_article(draft, &author).await.unwrap();
+    let update = |tag_list: TagListUpdate| ArticleUpdate {
+        title: None,
+        description: None,
+        body: None,
+        tag_list: Some(tag_list),
+        slug_follows_title: false,
+    };
+
+    let updated = repository
+        .update_article(
+            article.clone(),
+            update(TagListUpdate {
+                add: vec![" Vikings ".to_string(), "dragons".to_string()],
+                remove: vec!["TRAINING".to_string()],
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.content.tag_list, ["dragons", "vikings"]);
+    assert_eq!(
+        repository.get_article_by_slug(&article.slug).await.unwrap(),
+        updated
+    );
+
+    let replaced = repository
+        .update_article(
+            updated,
+            update(TagListUpdate {
+                replace: Some(vec!["Fantasy".to_string()]),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(replaced.content.tag_list, ["fantasy"]);
+    let tagged = repository
+        .find_articles(ArticleQuery {
+            tags: vec!["fantasy".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(tagged.items, vec![replaced]);
+}
+
+pub async fn rename_article(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let fan = create_user(repository).await.0;
+    let article = repository
+        .publish_article(
+            ArticleContent {
+                title: "How to train your dragon".to_string(),
+                ..article_content()
+            },
+            &author,
+        )
+        .await
+        .unwrap();
+    repository.favorite(&article, &fan).await.unwrap();
+    let comment = repository
+        .comment_article(&fan, &article, CommentContent("Nice".to_string()), None)
+        .await
+        .unwrap();
+    let rename = |title: &str| ArticleUpdate {
+        title: Some(title.to_string()),
+        description: None,
+        body: None,
+        tag_list: None,
+        slug_follows_title: true,
+    };
+
+    // Changing the case or the punctuation of the title keeps the slug
+    let updated = repository
+        .update_article(article.clone(), rename("How to train your dragon!"))
+        .await
+        .unwrap();
+    assert_eq!(updated.slug, article.slug);
+
+    let renamed = repository
+        .update_article(updated, rename("How to train your dragon, again"))
+        .await
+        .unwrap();
+    assert_eq!(renamed.slug, "how-to-train-your-dragon-again");
+    // Renaming keeps the identity of the article
+    assert_eq!(renamed.id, article.id);
+    assert_eq!(renamed.favorites_count, 1);
+    assert_eq!(
+        repository.get_article_by_slug(&renamed.slug).await.unwrap(),
+        renamed
+    );
+    assert!(repository.get_article_by_slug(&article.slug).await.is_err());
+    let comments = repository
+        .get_comments(&renamed, CommentQuery::default())
+        .await
+        .unwrap()
+        .comments
+        .items;
+    assert_eq!(comments, vec![comment]);
+    assert_eq!(
+        repository.get_current_slug(&article.slug).await.unwrap(),
+        Some(renamed.slug.clone())
+    );
+    assert_eq!(repository.get_current_slug("missing").await.unwrap(), None);
+
+    // Old slugs are not handed out to other articles
+    let other = repository
+        .publish_article(
+            ArticleContent {
+                title: "How to train your dragon".to_string(),
+                ..article_content()
+            },
+            &author,
+        )
+        .await
+        .unwrap();
+    assert_eq!(other.slug, format!("{}-2", article.slug));
+
+    // Every old slug points to the current one
+    let renamed_again = repository
+        .update_article(renamed.clone(), rename("Dragons"))
+        .await
+        .unwrap();
+    assert_eq!(renamed_again.slug, "dragons");
+    for old_slug in [&article.slug, &renamed.slug] {
+        assert_eq!(
+            repository.get_current_slug(old_slug).await.unwrap(),
+            Some(renamed_again.slug.clone())
+        );
+    }
+
+    // Going back to an old slug is fine
+    let back = repository
+        .update_article(renamed_again.clone(), rename("How to train your dragon"))
+        .await
+        .unwrap();
+    assert_eq!(back.slug, article.slug);
+    assert_eq!(
+        repository
+            .get_current_slug(&renamed_again.slug)
+            .await
+            .unwrap(),
+        Some(article.slug.clone())
+    );
+
+    // Without `slug_follows_title` the slug never changes
+    let update = ArticleUpdate {
+        slug_follows_title: false,
+        ..rename("Something else entirely")
+    };
+    let kept = repository.update_article(back, update).await.unwrap();
+    assert_eq!(kept.slug, article.slug);
+}
+
+pub async fn delete_article(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let article = create_article(repository, &author).await;
+    let other_article = create_article(repository, &author).await;
+
+    repository.delete_article(&article).await.unwrap();
+
+    assert!(repository.get_article_by_slug(&article.slug).await.is_err());
+    let articles = repository.find_articles(Default::default()).await.unwrap();
+    assert_eq!(articles.items, vec![other_article]);
+
+    // The article is gone: deleting it again fails
+    assert!(repository.delete_article(&article).await.is_err());
+}
+
+pub async fn find_articles(repository: &impl Repository) {
+    let first_author = create_user(repository).await.0;
+    let second_author = create_user(repository).await.0;
+
+    let mut draft = article_content();
+    draft.tag_list = vec!["rust".to_string(), "axum".to_string()];
+    let tagged = repository
+        .publish_article(draft, &first_author)
+        .await
+        .unwrap();
+    let untagged = create_article(repository, &first_author).await;
+    let other = create_article(repository, &second_author).await;
+
+    // Most recent articles come first
+    let articles = repository.find_articles(Default::default()).await.unwrap();
+    assert_eq!(
+        articles.items,
+        vec![other.clone(), untagged.clone(), tagged.clone()]
+    );
+    assert_eq!(articles.total, 3);
+
+    let query = ArticleQuery {
+        author: Some(first_author.profile.username.clone()),
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert_eq!(articles.items, vec![untagged, tagged.clone()]);
+
+    let query = ArticleQuery {
+        tags: vec!["rust".to_string()],
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert_eq!(articles.items, vec![tagged]);
+
+    let query = ArticleQuery {
+        author: Some(second_author.profile.username.clone()),
+        tags: vec!["rust".to_string()],
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert!(articles.items.is_empty());
+    assert_eq!(articles.total, 0);
+
+    let query = ArticleQuery {
+        author: Some(second_author.profile.username.clone()),
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert_eq!(articles.items, vec![other]);
+
+    let query = ArticleQuery {
+        author: Some("nobody".to_string()),
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert!(articles.items.is_empty());
+}
+
+pub async fn paginate_articles(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let mut articles = Vec::new();
+    for _ in 0..5 {
+        articles.push(create_article(repository, &author).await);
+    }
+    articles.reverse();
+
+    let query = ArticleQuery {
+        limit: Some(2),
+        offset: Some(1),
+        ..Default::default()
+    };
+    let page = repository.find_articles(query).await.unwrap();
+    assert_eq!(page.items, articles[1..3].to_vec());
+    // The total is not affected by limit and offset
+    assert_eq!(page.total, 5);
+
+    let query = ArticleQuery {
+        limit: Some(10),
+        offset: Some(4),
+        ..Default::default()
+    };
+    let page = repository.find_articles(query).await.unwrap();
+    assert_eq!(page.items, articles[4..].to_vec());
+    assert_eq!(page.total, 5);
+
+    let query = ArticleQuery {
+        offset: Some(5),
+        ..Default::default()
+    };
+    let page = repository.find_articles(query).await.unwrap();
+    assert!(page.items.is_empty());
+    assert_eq!(page.total, 5);
+}
+
+pub async fn find_favorited_articles(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let other_author = create_user(repository).await.0;
+    let fan = create_user(repository).await.0;
+
+    let mut draft = article_content();
+    draft.tag_list = vec!["rust".to_string()];
+    let tagged = repository.publish_article(draft, &author).await.unwrap();
+    let untagged = create_article(repository, &author).await;
+    let other = create_article(repository, &other_author).await;
+    let ignored = create_article(repository, &author).await;
+    for article in [&tagged, &untagged, &other] {
+        repository.favorite(article, &fan).await.unwrap();
+    }
+    // Favorites of somebody else do not leak into the results
+    repository.favorite(&ignored, &author).await.unwrap();
+
+    let slugs = |page: realworld_domain::Page<Article>| {
+        page.items.into_iter().map(|a| a.slug).collect::<Vec<_>>()
+    };
+
+    let query = ArticleQuery {
+        favorited: Some(fan.profile.username.clone()),
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert_eq!(articles.total, 3);
+    assert_eq!(
+        slugs(articles),
+        vec![
+            other.slug.clone(),
+            untagged.slug.clone(),
+            tagged.slug.clone()
+        ]
+    );
+
+    let query = ArticleQuery {
+        favorited: Some(fan.profile.username.clone()),
+        author: Some(author.profile.username.clone()),
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert_eq!(slugs(articles), vec![untagged.slug, tagged.slug.clone()]);
+
+    let query = ArticleQuery {
+        favorited: Some(fan.profile.username.clone()),
+        author: Some(author.profile.username.clone()),
+        tags: vec!["rust".to_string()],
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert_eq!(slugs(articles), vec![tagged.slug]);
+
+    let query = ArticleQuery {
+        favorited: Some(other_author.profile.username.clone()),
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert!(articles.items.is_empty());
+    assert_eq!(articles.total, 0);
+
+    let query = ArticleQuery {
+        favorited: Some("nobody".to_string()),
+        ..Default::default()
+    };
+    let articles = repository.find_articles(query).await.unwrap();
+    assert!(articles.items.is_empty());
+}
+
+pub async fn find_articles_by_tags(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let publish = |tags: &[&str]| {
+        let mut draft = article_content();
+        draft.tag_list = tags.iter().map(|t| t.to_string()).collect();
+        repository.publish_article(draft, &author)
+    };
+    let rust = publish(&["rust"]).await.unwrap().slug;
+    let rust_and_axum = publish(&["rust", "axum"]).await.unwrap().slug;
+    let axum = publish(&["axum"]).await.unwrap().slug;
+    let quoted = publish(&["it's", "\"quoted\""]).await.unwrap().slug;
+    let injection = publish(&["'; drop table articles; --", "%_\\"])
+        .await
+        .unwrap()
+        .slug;
+
+    let find = |tags: &[&str], tag_match: TagMatch| {
+        let query = ArticleQuery {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            tag_match,
+            ..Default::default()
+        };
+        async move {
+            let page = repository.find_articles(query).await.unwrap();
+            let mut slugs: Vec<String> = page.items.into_iter().map(|a| a.slug).collect();
+            slugs.sort();
+            slugs
+        }
+    };
+    let sorted = |mut slugs: Vec<String>| {
+        slugs.sort();
+        slugs
+    };
+
+    assert_eq!(
+        find(&["rust", "axum"], TagMatch::Any).await,
+        sorted(vec![rust.clone(), rust_and_axum.clone(), axum.clone()])
+    );
+    assert_eq!(
+        find(&["rust", "axum"], TagMatch::All).await,
+        vec![rust_and_axum.clone()]
+    );
+    assert_eq!(
+        find(&["rust", "missing"], TagMatch::Any).await,
+        sorted(vec![rust, rust_and_axum])
+    );
+    assert!(find(&["rust", "missing"], TagMatch::All).await.is_empty());
+
+    // Tags are matched literally, whatever characters they contain
+    assert_eq!(find(&["it's"], TagMatch::Any).await, vec![quoted.clone()]);
+    assert_eq!(find(&["\"quoted\""], TagMatch::Any).await, vec![quoted]);
+    assert_eq!(
+        find(&["'; drop table articles; --"], TagMatch::Any).await,
+        vec![injection.clone()]
+    );
+    assert_eq!(find(&["%_\\"], TagMatch::All).await, vec![injection]);
+    assert!(find(&["%"], TagMatch::Any).await.is_empty());
+    assert!(find(&["it"], TagMatch::Any).await.is_empty());
+
+    // All the articles are still there
+    let articles = repository.find_articles(Default::default()).await.unwrap();
+    assert_eq!(articles.total, 5);
+}
+
+pub async fn find_articles_by_unnormalized_tags(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let draft = article_content();
+    let tags = vec![" Rust ".to_string(), "Axum".to_string()];
+    let draft = ArticleContent::new(draft.title, draft.description, draft.body, tags).unwrap();
+    let article = repository.publish_article(draft, &author).await.unwrap();
+
+    // Filters are normalized like the tags they are matched against
+    for (tags, tag_match) in [
+        (vec!["Rust"], TagMatch::Any),
+        (vec!["RUST", " axum "], TagMatch::All),
+        (vec!["rust", "Rust"], TagMatch::All),
+    ] {
+        let query = ArticleQuery {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            tag_match,
+            ..Default::default()
+        };
+        let page = repository.find_articles(query).await.unwrap();
+        assert_eq!(page.items, vec![article.clone()], "{:?}", tags);
+    }
+}
+
+pub async fn favorite_and_unfavorite(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let fan = create_user(repository).await.0;
+    let article = create_article(repository, &author).await;
+
+    let outcome = repository.favorite(&article, &fan).await.unwrap();
+    assert!(matches!(outcome, FavoriteOutcome::NewFavorite));
+    let outcome = repository.favorite(&article, &fan).await.unwrap();
+    assert!(matches!(outcome, FavoriteOutcome::AlreadyAFavorite));
+
+    let article = repository.get_article_by_slug(&article.slug).await.unwrap();
+    assert_eq!(article.favorites_count, 1);
+    let view = repository
+        .get_article_view(&fan, article.clone())
+        .await
+        .unwrap();
+    assert!(view.favorited);
+    let view = repository
+        .get_article_view(&author, article.clone())
+        .await
+        .unwrap();
+    assert!(!view.favorited);
+
+    let outcome = repository.unfavorite(&article, &fan).await.unwrap();
+    assert!(matches!(outcome, UnfavoriteOutcome::WasAFavorite));
+    let outcome = repository.unfavorite(&article, &fan).await.unwrap();
+    assert!(matches!(outcome, UnfavoriteOutcome::WasNotAFavorite));
+
+    let article = repository.get_article_by_slug(&article.slug).await.unwrap();
+    assert_eq!(article.favorites_count, 0);
+}
+
+pub async fn favorite_a_missing_article(repository: &impl Repository) {
+    let user = create_user(repository).await.0;
+    let article = create_article(repository, &user).await;
+    repository.delete_article(&article).await.unwrap();
+
+    assert!(repository.favorite(&article, &user).await.is_err());
+}
+
+pub async fn concurrent_favorites_and_follows(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let fan = create_user(repository).await.0;
+    let article = create_article(repository, &author).await;
+
+    let outcomes = join_all((0..10).map(|_| repository.favorite(&article, &fan))).await;
+    let outcomes: Vec<FavoriteOutcome> = outcomes.into_iter().map(Result::unwrap).collect();
+    let new_favorites = outcomes
+        .iter()
+        .filter(|o| matches!(o, FavoriteOutcome::NewFavorite))
+        .count();
+    assert_eq!(new_favorites, 1);
+    let article = repository.get_article_by_slug(&article.slug).await.unwrap();
+    assert_eq!(article.favorites_count, 1);
+
+    let outcomes = join_all((0..10).map(|_| repository.unfavorite(&article, &fan))).await;
+    let unfavorites = outcomes
+        .into_iter()
+        .map(Result::unwrap)
+        .filter(|o| matches!(o, UnfavoriteOutcome::WasAFavorite))
+        .count();
+    assert_eq!(unfavorites, 1);
+    let article = repository.get_article_by_slug(&article.slug).await.unwrap();
+    assert_eq!(article.favorites_count, 0);
+
+    for result in join_all((0..10).map(|_| repository.follow(&fan, &author.profile))).await {
+        result.unwrap();
+    }
+    let view = repository
+        .get_profile_view(&fan, &author.profile.username)
+        .await
+        .unwrap();
+    assert!(view.following);
+}
+
+pub async fn sort_articles_by_favorites(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let fans = [
+        create_user(repository).await.0,
+        create_user(repository).await.0,
+    ];
+    let mut articles = vec![];
+    for n_fans in [0, 2, 1, 0] {
+        let article = create_article(repository, &author).await;
+        for fan in &fans[..n_fans] {
+            repository.favorite(&article, fan).await.unwrap();
+        }
+        articles.push(article.slug);
+    }
+    // Unfavorited articles count too
+    repository
+        .unfavorite(
+            &repository.get_article_by_slug(&articles[1]).await.unwrap(),
+            &fans[1],
+        )
+        .await
+        .unwrap();
+
+    let query = ArticleQuery {
+        sort: ArticleSort::MostFavorited,
+        ..Default::default()
+    };
+    let found = repository.find_articles(query).await.unwrap().items;
+    let found: Vec<(&str, u64)> = found
+        .iter()
+        .map(|a| (a.slug.as_str(), a.favorites_count))
+        .collect();
+    // Ties are broken by recency
+    let expected = [(2, 1), (1, 1), (3, 0), (0, 0)].map(|(i, n)| (articles[i].as_str(), n));
+    assert_eq!(found, expected);
+}
+
+pub async fn sort_articles(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let mut articles = vec![];
+    for _ in 0..3 {
+        articles.push(create_article(repository, &author).await);
+    }
+    let comment = |n: usize| {
+        let article = articles[n].clone();
+        let author = author.clone();
+        async move {
+            repository
+                .comment_article(
+                    &author,
+                    &article,
+                    CommentContent("First!".to_string()),
+                    None,
+                )
+                .await
+                .unwrap()
+        }
+    };
+    let deleted = comment(1).await;
+    comment(1).await;
+    comment(2).await;
+    let update = ArticleUpdate {
+        title: None,
+        description: None,
+        body: Some(fake!(Lorem.paragraph(10, 5)).to_string()),
+        tag_list: None,
+        slug_follows_title: false,
+    };
+    repository
+        .update_article(articles[0].clone(), update)
+        .await
+        .unwrap();
+
+    let ids: &Vec<_> = &articles.iter().map(|a| a.id).collect();
+    let sorted = |sort: ArticleSort| async move {
+        let query = ArticleQuery {
+            sort,
+            ..Default::default()
+        };
+        let found = repository.find_articles(query).await.unwrap().items;
+        found
+            .iter()
+            .map(|a| ids.iter().position(|id| *id == a.id).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(sorted(ArticleSort::Recent).await, [2, 1, 0]);
+    assert_eq!(sorted(ArticleSort::Oldest).await, [0, 1, 2]);
+    assert_eq!(sorted(ArticleSort::MostCommented).await, [1, 2, 0]);
+    assert_eq!(sorted(ArticleSort::Updated).await, [0, 2, 1]);
+
+    // Ties are broken by recency
+    repository.delete_comment(deleted.id).await.unwrap();
+    assert_eq!(sorted(ArticleSort::MostCommented).await, [2, 1, 0]);
+}
+
+pub async fn search_articles(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let other_author = create_user(repository).await.0;
+    let in_title = ArticleContent {
+        title: "The zebra".to_string(),
+        tag_list: vec!["savanna".to_string()],
+        ..article_content()
+    };
+    let in_title = repository.publish_article(in_title, &author).await.unwrap();
+    let in_body = ArticleContent {
+        title: "Stripes".to_string(),
+        body: format!("{} A zebra has stripes.", fake!(Lorem.paragraph(3, 5))),
+        ..article_content()
+    };
+    let in_body = repository
+        .publish_article(in_body, &other_author)
+        .await
+        .unwrap();
+    create_article(repository, &author).await;
+
+    let search = |text: &str, query: ArticleQuery| {
+        let text = SearchText::new(text.to_string()).unwrap();
+        async move { repository.search_articles(&text, query).await.unwrap() }
+    };
+    // Title matches weigh more than body ones, even on more recent articles
+    let hits = search("Zebra", ArticleQuery::default()).await;
+    assert_eq!(hits.total, 2);
+    let slugs: Vec<&str> = hits.items.iter().map(|h| h.article.slug.as_str()).collect();
+    assert_eq!(slugs, [in_title.slug.as_str(), in_body.slug.as_str()]);
+    assert!(hits.items[1].snippet.contains("<mark>zebra</mark>"));
+
+    // Every word must match
+    let hits = search("zebra stripes", ArticleQuery::default()).await;
+    assert_eq!(hits.total, 1);
+    assert_eq!(hits.items[0].article.slug, in_body.slug);
+
+    let query = ArticleQuery {
+        author: Some(other_author.profile.username.clone()),
+        ..Default::default()
+    };
+    let hits = search("zebra", query).await;
+    assert_eq!(hits.total, 1);
+    assert_eq!(hits.items[0].article.slug, in_body.slug);
+
+    let query = ArticleQuery {
+        tags: vec!["savanna".to_string()],
+        ..Default::default()
+    };
+    let hits = search("zebra", query).await;
+    assert_eq!(hits.total, 1);
+    assert_eq!(hits.items[0].article.slug, in_title.slug);
+
+    let query = ArticleQuery {
+        limit: Some(1),
+        offset: Some(1),
+        ..Default::default()
+    };
+    let hits = search("zebra", query).await;
+    assert_eq!(hits.total, 2);
+    assert_eq!(hits.items.len(), 1);
+    assert_eq!(hits.items[0].article.slug, in_body.slug);
+
+    assert_eq!(search("giraffe", ArticleQuery::default()).await.total, 0);
+
+    // Snippets are meant to be rendered: the only markup they contain is the highlighting
+    let with_html = ArticleContent {
+        body: "An okapi <script>alert('okapi')</script> & friends.".to_string(),
+        ..article_content()
+    };
+    repository
+        .publish_article(with_html, &author)
+        .await
+        .unwrap();
+    let hits = search("okapi", ArticleQuery::default()).await;
+    let snippet = &hits.items[0].snippet;
+    assert!(snippet.contains("<mark>okapi</mark>"));
+    assert!(snippet.contains("&lt;script&gt;"));
+    assert!(snippet.contains("&amp; friends"));
+    assert!(!snippet.contains("<script>"));
+}
+
+pub async fn articles_views(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let viewer = create_user(repository).await.0;
+    let favorite = create_article(repository, &author).await;
+    let other = create_article(repository, &author).await;
+
+    repository.favorite(&favorite, &viewer).await.unwrap();
+    repository.follow(&viewer, &author.profile).await.unwrap();
+
+    let favorite = repository
+        .get_article_by_slug(&favorite.slug)
+        .await
+        .unwrap();
+    let views = repository
+        .get_articles_views(&viewer, vec![favorite.clone(), other.clone()])
+        .await
+        .unwrap();
+
+    assert_eq!(views.len(), 2);
+    assert_eq!(views[0].slug, favorite.slug);
+    assert!(views[0].favorited);
+    assert_eq!(views[0].favorites_count, 1);
+    assert!(views[0].author.following);
+    assert_eq!(views[0].viewer, viewer.id);
+    assert_eq!(views[1].slug, other.slug);
+    assert!(!views[1].favorited);
+    assert_eq!(views[1].favorites_count, 0);
+}
+
+pub async fn feed(repository: &impl Repository) {
+    let reader = create_user(repository).await.0;
+    let followed = create_user(repository).await.0;
+    let stranger = create_user(repository).await.0;
+
+    let own = create_article(repository, &reader).await;
+    let followed_article = create_article(repository, &followed).await;
+    create_article(repository, &stranger).await;
+
+    repository.follow(&reader, &followed.profile).await.unwrap();
+
+    let query = FeedQuery {
+        limit: 20,
+        offset: 0,
+        cursor: None,
+    };
+    let feed = repository.feed(&reader, query).await.unwrap();
+    assert_eq!(feed.next_cursor, None);
+    let feed = feed.articles;
+    let slugs: Vec<String> = feed
+        .items
+        .into_iter()
+        .map(|view| {
+            assert!(view.author.following);
+            view.slug
+        })
+        .collect();
+
+    // Users always self-follow, hence their own articles are in their feed
+    assert_eq!(slugs, vec![followed_article.slug.clone(), own.slug.clone()]);
+    assert_eq!(feed.total, 2);
+
+    let query = FeedQuery {
+        limit: 1,
+        offset: 1,
+        cursor: None,
+    };
+    let feed = repository.feed(&reader, query).await.unwrap().articles;
+    let slugs: Vec<String> = feed.items.into_iter().map(|view| view.slug).collect();
+    assert_eq!(slugs, vec![own.slug]);
+    assert_eq!(feed.total, 2);
+}
+
+pub async fn feed_cursor(repository: &impl Repository) {
+    let reader = create_user(repository).await.0;
+    let mut slugs = vec![];
+    for _ in 0..3 {
+        slugs.push(create_article(repository, &reader).await.slug);
+    }
+
+    let reader = &reader;
+    let page = |cursor: Option<FeedCursor>| async move {
+        let query = FeedQuery {
+            limit: 2,
+            offset: 0,
+            cursor,
+        };
+        let feed = repository.feed(reader, query).await.unwrap();
+        let slugs: Vec<String> = feed.articles.items.into_iter().map(|a| a.slug).collect();
+        (slugs, feed.articles.total, feed.next_cursor)
+    };
+    let (first, total, cursor) = page(None).await;
+    assert_eq!(first, [slugs[2].clone(), slugs[1].clone()]);
+    assert_eq!(total, 3);
+    let cursor = cursor.unwrap();
+    assert_eq!(cursor.to_string().parse(), Ok(cursor));
+
+    // Articles published in between are neither repeated nor skipped
+    create_article(repository, reader).await;
+    let (second, total, cursor) = page(Some(cursor)).await;
+    assert_eq!(second, [slugs[0].clone()]);
+    assert_eq!(total, 4);
+    assert_eq!(cursor, None);
+}
+
+pub async fn comments_views(repository: &impl Repository) {
+    let followed = create_user(repository).await.0;
+    let stranger = create_user(repository).await.0;
+    let viewer = create_user(repository).await.0;
+    let article = create_article(repository, &followed).await;
+    repository.follow(&viewer, &followed.profile).await.unwrap();
+
+    let mut comments = vec![];
+    for (author, body) in [
+        (&followed, "First"),
+        (&stranger, "Second"),
+        (&viewer, "Third"),
+    ] {
+        let content = CommentContent(body.to_string());
+        let comment = repository.comment_article(author, &article, content, None);
+        comments.push(comment.await.unwrap());
+    }
+    let views = repository
+        .get_comments_views(&viewer, comments.clone())
+        .await
+        .unwrap();
+
+    for (view, comment) in views.iter().zip(&comments) {
+        assert_eq!(view.id, comment.id);
+        assert_eq!(view.body, comment.body);
+        assert_eq!(view.author.profile, comment.author);
+        assert_eq!(view.author.viewer, viewer.id);
+    }
+    let following: Vec<bool> = views.iter().map(|v| v.author.following).collect();
+    // Users always self-follow
+    assert_eq!(following, [true, false, true]);
+    assert!(repository
+        .get_comments_views(&viewer, vec![])
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+pub async fn comments(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let commenter = create_user(repository).await.0;
+    let article = create_article(repository, &author).await;
+    let other_article = create_article(repository, &author).await;
+
+    let first = repository
+        .comment_article(
+            &commenter,
+            &article,
+            CommentContent("First".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+    let second = repository
+        .comment_article(
+            &author,
+            &article,
+            CommentContent("Second".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+    repository
+        .comment_article(
+            &author,
+            &other_article,
+            CommentContent("Other".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(first.author, commenter.profile);
+    assert_eq!(first.body, "First");
+
+    assert_eq!(
+        repository.get_comment(&article, first.id).await.unwrap(),
+        first
+    );
+    // Comments are looked up on their own article only
+    let result = repository.get_comment(&other_article, first.id).await;
+    assert!(matches!(
+        result,
+        Err(ChangeCommentError::CommentNotFound { .. })
+    ));
+    // Comments are returned in the order they were posted
+    let comments = repository
+        .get_comments(&article, CommentQuery::default())
+        .await
+        .unwrap()
+        .comments
+        .items;
+    assert_eq!(comments, vec![first.clone(), second.clone()]);
+
+    repository.delete_comment(first.id).await.unwrap();
+    let result = repository.get_comment(&article, first.id).await;
+    assert!(matches!(
+        result,
+        Err(ChangeCommentError::CommentNotFound { .. })
+    ));
+    let comments = repository
+        .get_comments(&article, CommentQuery::default())
+        .await
+        .unwrap()
+        .comments
+        .items;
+    assert_eq!(comments, vec![second.clone()]);
+
+    // Deleting an article deletes its comments
+    repository.delete_article(&article).await.unwrap();
+    assert!(repository.get_comment(&article, second.id).await.is_err());
+}
+
+pub async fn edit_comment(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let article = create_article(repository, &author).await;
+    let comment = repository
+        .comment_article(&author, &article, CommentContent("Frist".to_string()), None)
+        .await
+        .unwrap();
+    assert!(!comment.edited());
+
+    let edited = repository
+        .update_comment(comment.id, CommentContent("First".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(edited.body, "First");
+    assert_eq!(edited.created_at, comment.created_at);
+    assert!(edited.edited());
+    assert_eq!(
+        repository.get_comment(&article, comment.id).await.unwrap(),
+        edited
+    );
+
+    repository.delete_comment(comment.id).await.unwrap();
+    let result = repository
+        .update_comment(comment.id, CommentContent("Second".to_string()))
+        .await;
+    assert!(matches!(
+        result,
+        Err(ChangeCommentError::CommentNotFound { .. })
+    ));
+}
+
+pub async fn comment_replies(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let article = create_article(repository, &author).await;
+    let other_article = create_article(repository, &author).await;
+    let comment = |article: &Article, parent_id: Option<u64>| {
+        let article = article.clone();
+        let author = author.clone();
+        async move {
+            repository
+                .comment_article(
+                    &author,
+                    &article,
+                    CommentContent("Indeed".to_string()),
+                    parent_id,
+                )
+                .await
+                .unwrap()
+        }
+    };
+    let parent = comment(&article, None).await;
+    let reply = comment(&article, Some(parent.id)).await;
+    comment(&article, Some(reply.id)).await;
+    comment(&other_article, None).await;
+    comment(&other_article, None).await;
+    assert_eq!(reply.parent_id, Some(parent.id));
+    assert_eq!(reply.article_id, article.id);
+    assert!(repository.has_replies(parent.id).await.unwrap());
+
+    let most_commented = || async {
+        let query = ArticleQuery {
+            sort: ArticleSort::MostCommented,
+            ..Default::default()
+        };
+        let found = repository.find_articles(query).await.unwrap().items;
+        found.into_iter().map(|a| a.id).collect::<Vec<_>>()
+    };
+    assert_eq!(most_commented().await, [article.id, other_article.id]);
+
+    // Tombstones stay in their thread, but are not counted anymore
+    repository.tombstone_comment(parent.id).await.unwrap();
+    let tombstone = repository.get_comment(&article, parent.id).await.unwrap();
+    assert!(tombstone.deleted);
+    assert_eq!(tombstone.body, "");
+    assert_eq!(
+        repository
+            .get_comments(&article, CommentQuery::default())
+            .await
+            .unwrap()
+            .comments
+            .items
+            .len(),
+        3
+    );
+    // Ties are broken by recency
+    assert_eq!(most_commented().await, [other_article.id, article.id]);
+
+    // Replies outlive their deleted parent, which takes the tombstone left without replies
+    repository.delete_comment(reply.id).await.unwrap();
+    let comments = repository
+        .get_comments(&article, CommentQuery::default())
+        .await
+        .unwrap()
+        .comments
+        .items;
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].parent_id, None);
+    assert!(repository.get_comment(&article, parent.id).await.is_err());
+}
+
+pub async fn delete_empty_tombstones(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let article = create_article(repository, &author).await;
+    let comment = |parent_id: Option<u64>| {
+        let (article, author) = (article.clone(), author.clone());
+        async move {
+            let content = CommentContent("Indeed".to_string());
+            let comment = repository.comment_article(&author, &article, content, parent_id);
+            comment.await.unwrap().id
+        }
+    };
+    let ids = || async {
+        let page = repository.get_comments(&article, CommentQuery::default());
+        let comments = page.await.unwrap().comments.items;
+        comments.into_iter().map(|c| c.id).collect::<Vec<_>>()
+    };
+
+    // A tombstone goes with its last reply, however deep, then its own tombstoned parent
+    let root = comment(None).await;
+    let reply = comment(Some(root)).await;
+    let first = comment(Some(reply)).await;
+    let second = comment(Some(reply)).await;
+    repository.tombstone_comment(root).await.unwrap();
+    repository.tombstone_comment(reply).await.unwrap();
+    repository.delete_comment(first).await.unwrap();
+    assert_eq!(ids().await, [root, reply, second]);
+    repository.delete_comment(second).await.unwrap();
+    assert!(ids().await.is_empty());
+
+    // Comments that are not deleted stay, and so do the tombstones above them
+    let root = comment(None).await;
+    let live = comment(Some(root)).await;
+    let reply = comment(Some(live)).await;
+    repository.tombstone_comment(root).await.unwrap();
+    repository.delete_comment(reply).await.unwrap();
+    assert_eq!(ids().await, [root, live]);
+    assert!(
+        repository
+            .get_comment(&article, root)
+            .await
+            .unwrap()
+            .deleted
+    );
+}
+
+pub async fn paginate_comments(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let article = create_article(repository, &author).await;
+    let other_article = create_article(repository, &author).await;
+    let comment = |article: &Article, parent_id: Option<u64>| {
+        let article = article.clone();
+        let author = author.clone();
+        async move {
+            repository
+                .comment_article(
+                    &author,
+                    &article,
+                    CommentContent("Indeed".to_string()),
+                    parent_id,
+                )
+                .await
+                .unwrap()
+                .id
+        }
+    };
+    let first = comment(&article, None).await;
+    let second = comment(&article, None).await;
+    comment(&other_article, None).await;
+    let reply = comment(&article, Some(second)).await;
+    let third = comment(&article, None).await;
+    let nested_reply = comment(&article, Some(reply)).await;
+
+    let page = |limit: Option<u64>, offset: u64, cursor: Option<u64>, order: CommentOrder| {
+        let article = article.clone();
+        async move {
+            let query = CommentQuery {
+                limit,
+                offset,
+                cursor,
+                order,
+                max_replies: None,
+            };
+            let page = repository.get_comments(&article, query).await.unwrap();
+            let ids: Vec<u64> = page.comments.items.iter().map(|c| c.id).collect();
+            (ids, page.comments.total, page.next_cursor)
+        }
+    };
+    use CommentOrder::{Asc, Desc};
+    // Threads come whole, top-level comments first
+    assert_eq!(
+        page(None, 0, None, Asc).await,
+        (vec![first, second, third, reply, nested_reply], 3, None)
+    );
+    assert_eq!(
+        page(Some(1), 0, None, Asc).await,
+        (vec![first], 3, Some(first))
+    );
+    assert_eq!(
+        page(Some(1), 0, Some(first), Asc).await,
+        (vec![second, reply, nested_reply], 3, Some(second))
+    );
+    assert_eq!(
+        page(Some(1), 0, Some(second), Asc).await,
+        (vec![third], 3, None)
+    );
+    assert_eq!(
+        page(Some(2), 0, None, Desc).await,
+        (vec![third, second, reply, nested_reply], 3, Some(second))
+    );
+    assert_eq!(
+        page(Some(2), 0, Some(second), Desc).await,
+        (vec![first], 3, None)
+    );
+    assert_eq!(
+        page(Some(5), 1, Some(third), Desc).await,
+        (vec![first], 3, None)
+    );
+
+    // Long threads are cut, keeping the first replies, but their size is known
+    let query = CommentQuery {
+        max_replies: Some(1),
+        ..Default::default()
+    };
+    let page = repository.get_comments(&article, query).await.unwrap();
+    let ids: Vec<u64> = page.comments.items.iter().map(|c| c.id).collect();
+    assert_eq!(ids, [first, second, third, reply]);
+    assert_eq!(page.replies_counts, HashMap::from([(second, 2)]));
+}
+
+pub async fn tags(repository: &impl Repository) {
+    let author = create_user(repository).await.0;
+    let mut articles = vec![];
+    for tags in [
+        vec!["rust", "web"],
+        vec!["rust", "db"],
+        vec!["web", "rust"],
+        vec![],
+    ] {
+        let mut draft = article_content();
+        draft.tag_list = tags.into_iter().map(String::from).collect();
+        articles.push(repository.publish_article(draft, &author).await.unwrap());
+    }
+
+    let counts = |tags: &[(&str, u64)]| {
+        tags.iter()
+            .map(|(tag, count)| TagCount {
+                tag: tag.to_string(),
+                count: *count,
+            })
+            .collect::<Vec<_>>()
+    };
+    // The most used first, ties broken by name
+    let tags = repository.get_tags(None).await.unwrap();
+    assert_eq!(tags, counts(&[("rust", 3), ("web", 2), ("db", 1)]));
+    let tags = repository.get_tags(Some(2)).await.unwrap();
+    assert_eq!(tags, counts(&[("rust", 3), ("web", 2)]));
+
+    // Counts follow the changes to the articles
+    let update = ArticleUpdate {
+        title: None,
+        description: None,
+        body: None,
+        tag_list: Some(TagListUpdate {
+            replace: Some(vec!["db".to_string()]),
+            ..Default::default()
+        }),
+        slug_follows_title: false,
+    };
+    let first = articles.remove(0);
+    repository.update_article(first, update).await.unwrap();
+    repository.delete_article(&articles[0]).await.unwrap();
+    let tags = repository.get_tags(None).await.unwrap();
+    assert_eq!(tags, counts(&[("db", 1), ("rust", 1), ("web", 1)]));
+}
+
+pub async fn sessions(repository: &impl Repository) {
+    let user = create_user(repository).await.0;
+    let other_user = create_user(repository).await.0;
+    let expires_at = Utc::now() + Duration::days(1);
+
+    let refresh_token = RefreshToken::generate();
+    let session = repository
+        .create_session(&user, &refresh_token, expires_at)
+        .await
+        .unwrap();
+    assert_eq!(session.user_id, user.id);
+    assert!(session.is_active());
+    assert_eq!(repository.get_session(session.id).await.unwrap(), session);
+    assert_eq!(
+        repository
+            .get_session_by_refresh_token(&refresh_token)
+            .await
+            .unwrap(),
+        session
+    );
+    let unknown = RefreshToken::generate();
+    assert!(matches!(
+        repository.get_session_by_refresh_token(&unknown).await,
+        Err(GetSessionError::NotFoundByRefreshToken { .. })
+    ));
+
+    let rotated = RefreshToken::generate();
+    let later = expires_at + Duration::days(1);
+    let updated = repository
+        .rotate_refresh_token(&session, &refresh_token, &rotated, later)
+        .await
+        .unwrap();
+    assert_eq!(updated.id, session.id);
+    // As when two refreshes race with the same token: the loser has a stale one
+    assert!(matches!(
+        repository
+            .rotate_refresh_token(&session, &refresh_token, &RefreshToken::generate(), later)
+            .await,
+        Err(RefreshSessionError::InvalidRefreshToken)
+    ));
+    assert!(matches!(
+        repository
+            .get_session_by_refresh_token(&refresh_token)
+            .await,
+        Err(GetSessionError::NotFoundByRefreshToken { .. })
+    ));
+    let retrieved = repository
+        .get_session_by_refresh_token(&rotated)
+        .await
+        .unwrap();
+    assert_eq!(retrieved.id, session.id);
+    assert_eq!(retrieved.expires_at.timestamp(), later.timestamp());
+
+    let second = repository
+        .create_session(&user, &RefreshToken::generate(), expires_at)
+        .await
+        .unwrap();
+    let others = repository
+        .create_session(&other_user, &RefreshToken::generate(), expires_at)
+        .await
+        .unwrap();
+
+    repository.revoke_session(session.id).await.unwrap();
+    assert!(!repository
+        .get_session(session.id)
+        .await
+        .unwrap()
+        .is_active());
+    assert!(matches!(
+        repository
+            .rotate_refresh_token(&session, &rotated, &RefreshToken::generate(), later)
+            .await,
+        Err(RefreshSessionError::InvalidRefreshToken)
+    ));
+    assert!(repository.get_session(second.id).await.unwrap().is_active());
+
+    repository.revoke_sessions(&user).await.unwrap();
+    assert!(!repository.get_session(second.id).await.unwrap().is_active());
+    assert!(repository.get_session(others.id).await.unwrap().is_active());
+
+    let missing = uuid::Uuid::new_v4();
+    assert!(matches!(
+        repository.get_session(missing).await,
+        Err(GetSessionError::NotFound { .. })
+    ));
+}
+
+pub async fn refresh_sessions(repository: &impl Repository) {
+    let user = create_user(repository).await.0;
+
+    let (session, refresh_token) = Session::start(&user, Duration::days(1), repository)
+        .await
+        .unwrap();
+    let (refreshed, new_refresh_token) =
+        Session::refresh(&refresh_token, Duration::days(1), repository)
+            .await
+            .unwrap();
+    assert_eq!(refreshed.id, session.id);
+    assert_ne!(new_refresh_token, refresh_token);
+
+    // A refresh token can be used only once
+    assert!(matches!(
+        Session::refresh(&refresh_token, Duration::days(1), repository).await,
+        Err(RefreshSessionError::InvalidRefreshToken)
+    ));
+
+    refreshed.revoke(repository).await.unwrap();
+    assert!(matches!(
+        Session::refresh(&new_refresh_token, Duration::days(1), repository).await,
+        Err(RefreshSessionError::InvalidRefreshToken)
+    ));
+
+    let (_, expired) = Session::start(&user, Duration::seconds(-1), repository)
+        .await
+        .unwrap();
+    assert!(matches!(
+        Session::refresh(&expired, Duration::days(1), repository).await,
+        Err(RefreshSessionError::InvalidRefreshToken)
+    ));
+}
@@ -1,17 +1,17 @@
-use crate::{articles, comments, context::ApplicationContext, profiles, users};
+use crate::{articles, comments, context::ApplicationContext, profiles, settings::Settings, users};
 use axum::{
     http::Method,
-    routing::{delete, get, post},
+    routing::{get, post, put},
     Extension, Router,
 };
 use domain::repositories::Repository;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
-pub fn get_app<R: Repository + Send + Sync + 'static>(repository: R) -> Router {
+pub fn get_app<R: Repository + Send + Sync + 'static>(repository: R, settings: Settings) -> Router {
     Router::new()
         .nest("/api", api())
-        .layer(Extension(ApplicationContext::new(repository)))
+        .layer(Extension(ApplicationContext::new(repository, settings)))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
@@ -28,6 +28,9 @@ pub fn api() -> Router {
         )
         .route("/users", post(users::register))
         .route("/users/login", post(users::login))
+        .route("/users/token/refresh", post(users::refresh_token))
+        .route("/users/logout", post(users::logout))
+        .route("/users/logout-all", post(users::logout_all))
         .route("/profiles/:username", get(profiles::get_profile))
         .route(
             "/profiles/:username/follow",
@@ -39,6 +42,7 @@ pub fn api() -> Router {
             get(articles::list_articles).post(articles::insert_article),
         )
         .route("/articles/feed", get(articles::feed))
+        .route("/articles/search", get(articles::search_articles))
         .route(
             "/articles/:slug",
             get(articles::get_article)
@@ -49,7 +53,10 @@ pub fn api() -> Router {
             "/articles/:slug/comments",
             get(comments::get).post(comments::create),
         )
-        .route("/articles/:slug/comments/:id", delete(comments::delete))
+        .route(
+            "/articles/:slug/comments/:id",
+            put(comments::update).delete(comments::delete),
+        )
         .route(
             "/articles/:slug/favorite",
             post(articles::favorite).delete(articles::unfavorite),
@@ -1,7 +1,11 @@
-use axum::{extract::Path, Extension};
+use axum::Extension;
 use domain::repositories::Repository;
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Path, User},
+};
 
 pub async fn delete_article(
     ctx: Extension<ApplicationContext>,
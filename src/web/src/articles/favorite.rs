@@ -1,8 +1,12 @@
-use axum::{extract::Path, Extension, Json};
+use axum::{Extension, Json};
 use domain::repositories::Repository;
 
 use super::responses::ArticleResponse;
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Path, User},
+};
 
 // pub async fn favorite<R: 'static + Repository + Sync + Send>(
 //     cx: Request<Context<R>>,
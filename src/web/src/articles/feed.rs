@@ -1,34 +1,55 @@
-use axum::{extract::Query, Extension, Json};
-use domain::repositories::Repository;
+use axum::{Extension, Json};
+use domain::{repositories::Repository, FeedCursor, FeedPage, ValidationError};
 use serde::{Deserialize, Serialize};
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Query, User},
+    settings::Pagination,
+};
 
-use super::responses::ArticlesResponse;
+use super::responses::Article;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct FeedQuery {
-    #[serde(default)]
-    pub limit: u64,
-
-    #[serde(default)]
-    pub offset: u64,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    /// The `nextCursor` of the previous page: unlike `offset`, it neither skips nor repeats
+    /// articles when new ones get published in between.
+    pub cursor: Option<String>,
 }
 
-impl Default for FeedQuery {
-    fn default() -> Self {
-        Self {
-            limit: 20,
-            offset: 0,
-        }
+impl FeedQuery {
+    fn into_domain(self, pagination: &Pagination) -> Result<domain::FeedQuery, ValidationError> {
+        let cursor = self
+            .cursor
+            .map(|cursor| cursor.parse::<FeedCursor>())
+            .transpose()?;
+        Ok(domain::FeedQuery {
+            limit: pagination.limit(self.limit),
+            offset: self.offset.unwrap_or_default(),
+            cursor,
+        })
     }
 }
 
-impl From<FeedQuery> for domain::FeedQuery {
-    fn from(f: FeedQuery) -> Self {
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedResponse {
+    pub articles: Vec<Article>,
+    pub articles_count: u64,
+    /// The `cursor` to the next page, if there is one.
+    pub next_cursor: Option<String>,
+}
+
+impl From<FeedPage> for FeedResponse {
+    fn from(page: FeedPage) -> Self {
         Self {
-            limit: f.limit,
-            offset: f.offset,
+            articles: page.articles.items.into_iter().map(Article::from).collect(),
+            // The number of articles in the feed, not the size of the page
+            articles_count: page.articles.total,
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
         }
     }
 }
@@ -37,11 +58,12 @@ pub async fn feed(
     ctx: Extension<ApplicationContext>,
     user: User,
     Query(query): Query<FeedQuery>,
-) -> ApiResult<Json<ArticlesResponse>> {
+) -> ApiResult<Json<FeedResponse>> {
     let user = ctx.repo().get_user_by_id(user.user_id()).await?;
 
-    let articles = user.feed(query.into(), ctx.repo()).await?;
-    let response = ArticlesResponse::from(articles);
+    let query = query.into_domain(&ctx.settings().pagination)?;
+    let articles = user.feed(query, ctx.repo()).await?;
+    let response = FeedResponse::from(articles);
 
     Ok(response.into())
 }
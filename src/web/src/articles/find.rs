@@ -1,7 +1,14 @@
-use axum::{extract::Path, Extension, Json};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
 use domain::repositories::Repository;
+use domain::GetArticleError;
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Path, User},
+};
 
 use super::responses::ArticleResponse;
 
@@ -9,15 +16,34 @@ pub async fn get_article(
     ctx: Extension<ApplicationContext>,
     Path(slug): Path<String>,
     user: Option<User>,
-) -> ApiResult<Json<ArticleResponse>> {
-    let article = ctx.repo().get_article_by_slug(&slug).await?;
+) -> ApiResult<Response> {
+    let article = match ctx.repo().get_article_by_slug(&slug).await {
+        Ok(article) => article,
+        Err(e @ GetArticleError::ArticleNotFound { .. }) => {
+            // Links to an article keep working after its title, hence its slug, changed
+            return match ctx.repo().get_current_slug(&slug).await? {
+                Some(current_slug) => Ok(moved_permanently(&current_slug)),
+                None => Err(e.into()),
+            };
+        }
+        Err(e) => return Err(e.into()),
+    };
 
     match user {
         Some(user) => {
             let user = ctx.repo().get_user_by_id(user.user_id()).await?;
             let article_view = ctx.repo().get_article_view(&user, article).await?;
-            Ok(ArticleResponse::from(article_view).into())
+            Ok(Json(ArticleResponse::from(article_view)).into_response())
         }
-        None => Ok(ArticleResponse::from(article).into()),
+        None => Ok(Json(ArticleResponse::from(article)).into_response()),
     }
 }
+
+fn moved_permanently(slug: &str) -> Response {
+    let location = format!("/api/articles/{}", slug);
+    (
+        StatusCode::MOVED_PERMANENTLY,
+        [(header::LOCATION, location)],
+    )
+        .into_response()
+}
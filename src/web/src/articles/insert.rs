@@ -1,8 +1,12 @@
-use axum::{Extension, Json};
+use axum::Extension;
 use domain::repositories::Repository;
 use serde::{Deserialize, Serialize};
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Json, User},
+};
 
 use super::responses::ArticleResponse;
 
@@ -21,14 +25,16 @@ pub struct NewArticleRequest {
     pub tag_list: Option<Vec<String>>,
 }
 
-impl From<NewArticleRequest> for domain::ArticleContent {
-    fn from(a: NewArticleRequest) -> domain::ArticleContent {
-        domain::ArticleContent {
-            title: a.title,
-            description: a.description,
-            body: a.body,
-            tag_list: a.tag_list.unwrap_or_default(),
-        }
+impl TryFrom<NewArticleRequest> for domain::ArticleContent {
+    type Error = domain::ValidationError;
+
+    fn try_from(a: NewArticleRequest) -> Result<Self, Self::Error> {
+        Self::new(
+            a.title,
+            a.description,
+            a.body,
+            a.tag_list.unwrap_or_default(),
+        )
     }
 }
 
@@ -38,7 +44,9 @@ pub async fn insert_article(
     request: Json<Request>,
 ) -> ApiResult<Json<ArticleResponse>> {
     let author = ctx.repo().get_user_by_id(user.user_id()).await?;
-    let published_article = author.publish(request.0.article.into(), ctx.repo()).await?;
+    let published_article = author
+        .publish(request.0.article.try_into()?, ctx.repo())
+        .await?;
 
     Ok(ArticleResponse::from(published_article).into())
 }
@@ -1,8 +1,12 @@
-use axum::{extract::Query, Extension, Json};
-use domain::repositories::Repository;
+use axum::{Extension, Json};
+use domain::{repositories::Repository, ArticleSort, Page, TagMatch};
 use serde::Deserialize;
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Query, User},
+};
 
 use super::responses::ArticlesResponse;
 
@@ -10,7 +14,16 @@ use super::responses::ArticlesResponse;
 pub struct ArticleQuery {
     pub author: Option<String>,
     pub favorited: Option<String>,
-    pub tag: Option<String>,
+    /// Collected from the, possibly repeated, `tag` parameters by [`list_articles`].
+    #[serde(skip)]
+    pub tags: Vec<String>,
+    #[serde(default, rename = "tagMatch")]
+    pub tag_match: TagMatch,
+    /// `recent` (the default), `oldest`, `most_favorited`, `most_commented` or `updated`.
+    #[serde(default)]
+    pub sort: ArticleSort,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
 }
 
 impl From<ArticleQuery> for domain::ArticleQuery {
@@ -18,23 +31,35 @@ impl From<ArticleQuery> for domain::ArticleQuery {
         Self {
             author: q.author,
             favorited: q.favorited,
-            tag: q.tag,
+            tags: q.tags,
+            tag_match: q.tag_match,
+            sort: q.sort,
+            limit: q.limit,
+            offset: q.offset,
         }
     }
 }
 
 pub async fn list_articles(
     ctx: Extension<ApplicationContext>,
-    Query(query): Query<ArticleQuery>,
+    Query(mut query): Query<ArticleQuery>,
+    Query(params): Query<Vec<(String, String)>>,
     user: Option<User>,
 ) -> ApiResult<Json<ArticlesResponse>> {
-    let articles = ctx.repo().find_articles(query.into()).await?;
+    query.tags = params
+        .into_iter()
+        .filter(|(key, _)| key == "tag")
+        .map(|(_, tag)| tag)
+        .collect();
+    let mut query: domain::ArticleQuery = query.into();
+    query.limit = Some(ctx.settings().pagination.limit(query.limit));
+    let articles = ctx.repo().find_articles(query).await?;
 
     match user {
         Some(user) => {
             let user = ctx.repo().get_user_by_id(user.user_id()).await?;
-            let views = ctx.repo().get_articles_views(&user, articles).await?;
-            Ok(ArticlesResponse::from(views).into())
+            let views = ctx.repo().get_articles_views(&user, articles.items).await?;
+            Ok(ArticlesResponse::from(Page::new(views, articles.total)).into())
         }
         None => Ok(ArticlesResponse::from(articles).into()),
     }
@@ -5,6 +5,7 @@ pub mod find;
 pub mod insert;
 pub mod list;
 pub mod responses;
+pub mod search;
 pub mod tags;
 pub mod update;
 
@@ -14,5 +15,6 @@ pub use feed::feed;
 pub use find::get_article;
 pub use insert::insert_article;
 pub use list::list_articles;
+pub use search::search_articles;
 pub use tags::tags;
 pub use update::update_article;
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use domain::Profile;
+use domain::{Page, Profile};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -9,13 +9,13 @@ pub struct ArticlesResponse {
     pub articles_count: u64,
 }
 
-impl<T: Into<Article>> From<Vec<T>> for ArticlesResponse {
-    fn from(articles: Vec<T>) -> Self {
-        let articles_count = articles.len() as u64;
-        let articles = articles.into_iter().map(|a| a.into()).collect();
+impl<T: Into<Article>> From<Page<T>> for ArticlesResponse {
+    fn from(page: Page<T>) -> Self {
+        let articles = page.items.into_iter().map(|a| a.into()).collect();
         Self {
             articles,
-            articles_count,
+            // The number of articles matching the query, not the size of the page
+            articles_count: page.total,
         }
     }
 }
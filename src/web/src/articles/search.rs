@@ -0,0 +1,74 @@
+use axum::{Extension, Json};
+use domain::{repositories::Repository, SearchText};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Query, User},
+};
+
+use super::{list::ArticleQuery, responses::Article};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResponse {
+    /// The most relevant articles first.
+    pub articles: Vec<SearchHit>,
+    pub articles_count: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub article: Article,
+    /// An HTML-escaped excerpt of the article, with the matching words wrapped in `<mark>` tags.
+    pub snippet: String,
+}
+
+/// Searches the title, description and body of articles for `q`, which accepts quoted
+/// phrases, `or` and `-excluded` words. The filters of [`super::list_articles`] apply, but
+/// not its `sort`.
+pub async fn search_articles(
+    ctx: Extension<ApplicationContext>,
+    Query(mut query): Query<ArticleQuery>,
+    Query(params): Query<Vec<(String, String)>>,
+    user: Option<User>,
+) -> ApiResult<Json<SearchResponse>> {
+    let mut text = String::new();
+    for (key, value) in params {
+        match key.as_str() {
+            "q" => text = value,
+            "tag" => query.tags.push(value),
+            _ => {}
+        }
+    }
+    let text = SearchText::new(text)?;
+    let mut query: domain::ArticleQuery = query.into();
+    query.limit = Some(ctx.settings().pagination.limit(query.limit));
+    let hits = ctx.repo().search_articles(&text, query).await?;
+
+    let (articles, snippets): (Vec<_>, Vec<_>) = hits
+        .items
+        .into_iter()
+        .map(|hit| (hit.article, hit.snippet))
+        .unzip();
+    let articles: Vec<Article> = match user {
+        Some(user) => {
+            let user = ctx.repo().get_user_by_id(user.user_id()).await?;
+            let views = ctx.repo().get_articles_views(&user, articles).await?;
+            views.into_iter().map(Article::from).collect()
+        }
+        None => articles.into_iter().map(Article::from).collect(),
+    };
+    let response = SearchResponse {
+        articles: articles
+            .into_iter()
+            .zip(snippets)
+            .map(|(article, snippet)| SearchHit { article, snippet })
+            .collect(),
+        articles_count: hits.total,
+    };
+
+    Ok(response.into())
+}
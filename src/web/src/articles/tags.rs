@@ -2,17 +2,49 @@ use axum::{Extension, Json};
 use domain::repositories::Repository;
 use serde::{Deserialize, Serialize};
 
-use crate::{context::ApplicationContext, errors::ApiResult};
+use crate::{context::ApplicationContext, errors::ApiResult, extractor::Query};
 
+#[derive(Default, Deserialize, Debug, Clone)]
+pub struct TagsQuery {
+    /// Whether each tag comes with the number of articles using it.
+    #[serde(default, rename = "withCounts")]
+    pub with_counts: bool,
+    /// Maximum number of tags to return: all tags are returned if `None`.
+    pub limit: Option<u64>,
+}
+
+/// Tags, the most used first.
 #[derive(Serialize, Deserialize)]
 pub struct TagsResponse {
-    pub tags: Vec<String>,
+    pub tags: Vec<TagResponse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum TagResponse {
+    Name(String),
+    WithCount { tag: String, count: u64 },
 }
 
-pub async fn tags(ctx: Extension<ApplicationContext>) -> ApiResult<Json<TagsResponse>> {
-    let tags = ctx.repo().get_tags().await?;
+pub async fn tags(
+    ctx: Extension<ApplicationContext>,
+    Query(query): Query<TagsQuery>,
+) -> ApiResult<Json<TagsResponse>> {
+    let limit = query
+        .limit
+        .map(|limit| ctx.settings().pagination.limit(Some(limit)));
+    let tags = ctx.repo().get_tags(limit).await?;
     let response = TagsResponse {
-        tags: tags.into_iter().collect(),
+        tags: tags
+            .into_iter()
+            .map(|t| match query.with_counts {
+                true => TagResponse::WithCount {
+                    tag: t.tag,
+                    count: t.count,
+                },
+                false => TagResponse::Name(t.tag),
+            })
+            .collect(),
     };
 
     Ok(response.into())
@@ -1,8 +1,12 @@
-use axum::{extract::Path, Extension, Json};
-use domain::{repositories::Repository, ArticleUpdate};
+use axum::Extension;
+use domain::{repositories::Repository, ArticleUpdate, TagListUpdate};
 use serde::{Deserialize, Serialize};
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Json, Path, User},
+};
 
 use super::responses::ArticleResponse;
 
@@ -18,15 +22,30 @@ pub struct UpdateArticleRequest {
     pub title: Option<String>,
     pub description: Option<String>,
     pub body: Option<String>,
+    /// Replaces the tags of the article.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_list: Option<Vec<String>>,
+    /// Tags added to the article, after `tagList` is applied.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub add_tags: Vec<String>,
+    /// Tags removed from the article, after `tagList` and `addTags` are applied.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove_tags: Vec<String>,
 }
 
-impl From<Request> for ArticleUpdate {
-    fn from(r: Request) -> ArticleUpdate {
-        ArticleUpdate {
-            title: r.article.title,
-            body: r.article.body,
-            description: r.article.description,
-        }
+impl TryFrom<Request> for ArticleUpdate {
+    type Error = domain::ValidationError;
+
+    fn try_from(r: Request) -> Result<Self, Self::Error> {
+        let a = r.article;
+        let changes_tags =
+            a.tag_list.is_some() || !a.add_tags.is_empty() || !a.remove_tags.is_empty();
+        let tag_list = changes_tags.then_some(TagListUpdate {
+            replace: a.tag_list,
+            add: a.add_tags,
+            remove: a.remove_tags,
+        });
+        Self::new(a.title, a.description, a.body, tag_list)
     }
 }
 
@@ -38,9 +57,11 @@ pub async fn update_article(
 ) -> ApiResult<Json<ArticleResponse>> {
     let article = ctx.repo().get_article_by_slug(&slug).await?;
     let user = ctx.repo().get_user_by_id(user.user_id()).await?;
-    let updated_article = user
-        .update_article(article, request.0.into(), ctx.repo())
-        .await?;
+    // Non-authors are turned away whatever they send
+    user.check_article_author(&article)?;
+    let mut update: ArticleUpdate = request.0.try_into()?;
+    update.slug_follows_title = ctx.settings().articles.slug_follows_title;
+    let updated_article = user.update_article(article, update, ctx.repo()).await?;
 
     let response: ArticleResponse = ctx
         .repo()
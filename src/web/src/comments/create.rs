@@ -1,8 +1,12 @@
-use axum::{extract::Path, Extension, Json};
+use axum::Extension;
 use domain::{repositories::Repository, CommentContent};
 use serde::{Deserialize, Serialize};
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Json, Path, User},
+};
 
 use super::responses::CommentResponse;
 
@@ -16,6 +20,8 @@ pub struct Request {
 #[serde(rename_all = "camelCase")]
 pub struct NewCommentRequest {
     pub body: String,
+    /// The id of the comment this one replies to, on the same article.
+    pub parent_id: Option<u64>,
 }
 
 pub async fn create(
@@ -24,11 +30,17 @@ pub async fn create(
     Path(slug): Path<String>,
     request: Json<Request>,
 ) -> ApiResult<Json<CommentResponse>> {
+    let request = request.0.comment;
+    let content = CommentContent::new(request.body)?;
     let author = ctx.repo().get_user_by_id(user.user_id()).await?;
     let article = ctx.repo().get_article_by_slug(&slug).await?;
-    let posted_comment = author
-        .comment(&article, CommentContent(request.0.comment.body), ctx.repo())
-        .await?;
+    let posted_comment = match request.parent_id {
+        Some(parent_id) => {
+            let parent = ctx.repo().get_comment(&article, parent_id).await?;
+            author.reply(&article, &parent, content, ctx.repo()).await?
+        }
+        None => author.comment(&article, content, ctx.repo()).await?,
+    };
 
     let response = CommentResponse {
         comment: posted_comment.into(),
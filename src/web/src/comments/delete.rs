@@ -1,15 +1,20 @@
-use axum::{extract::Path, Extension};
+use axum::Extension;
 use domain::repositories::Repository;
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Path, User},
+};
 
 pub async fn delete(
     ctx: Extension<ApplicationContext>,
     user: User,
-    Path((_, comment_id)): Path<(String, u64)>,
+    Path((slug, comment_id)): Path<(String, u64)>,
 ) -> ApiResult<()> {
     let author = ctx.repo().get_user_by_id(user.user_id()).await?;
-    let comment = ctx.repo().get_comment(comment_id).await?;
+    let article = ctx.repo().get_article_by_slug(&slug).await?;
+    let comment = ctx.repo().get_comment(&article, comment_id).await?;
     author.delete_comment(comment, ctx.repo()).await?;
 
     Ok(())
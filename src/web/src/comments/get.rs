@@ -1,30 +1,79 @@
-use axum::{extract::Path, Extension, Json};
-use domain::repositories::Repository;
-use futures::{future::join_all, FutureExt};
+use axum::{Extension, Json};
+use domain::{repositories::Repository, CommentOrder, CommentQuery};
+use serde::{Deserialize, Serialize};
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Path, Query, User},
+};
 
-use super::responses::CommentsResponse;
+use super::responses::{flat, tree, Comment, CommentsResponse};
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentsFormat {
+    /// A list of comments, where replies follow their parent.
+    #[default]
+    Flat,
+    /// Top-level comments, with their replies nested in them.
+    Tree,
+}
+
+/// `limit`, `offset` and `cursor` count threads, that is top-level comments with their
+/// replies.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct CommentsQuery {
+    #[serde(default)]
+    pub format: CommentsFormat,
+    /// `asc` (the default) for the oldest threads first, `desc` for the most recent first.
+    #[serde(default)]
+    pub order: CommentOrder,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    /// The `nextCursor` of the previous page.
+    pub cursor: Option<u64>,
+    /// The number of replies per thread, the first posted: bounded like `limit`.
+    pub replies: Option<u64>,
+}
 
 pub async fn get(
     ctx: Extension<ApplicationContext>,
     user: Option<User>,
     Path(slug): Path<String>,
+    Query(query): Query<CommentsQuery>,
 ) -> ApiResult<Json<CommentsResponse>> {
     let article = ctx.repo().get_article_by_slug(&slug).await?;
-    let comments = article.comments(ctx.repo()).await?;
+    let page_query = CommentQuery {
+        limit: Some(ctx.settings().pagination.limit(query.limit)),
+        offset: query.offset.unwrap_or_default(),
+        cursor: query.cursor,
+        order: query.order,
+        max_replies: Some(ctx.settings().pagination.limit(query.replies)),
+    };
+    let page = article.comments(page_query, ctx.repo()).await?;
+    let (comments, total) = (page.comments.items, page.comments.total);
 
-    let response: CommentsResponse = match user {
+    let comments: Vec<Comment> = match user {
         Some(user) => {
             let user = ctx.repo().get_user_by_id(user.user_id()).await?;
-            let result: Vec<_> = comments
-                .into_iter()
-                .map(|c| c.view(&user, ctx.repo()).boxed())
-                .collect();
-            let comment_views: Result<Vec<_>, _> = join_all(result).await.into_iter().collect();
-            CommentsResponse::from(comment_views?)
+            let views = ctx.repo().get_comments_views(&user, comments).await?;
+            views.into_iter().map(Comment::from).collect()
         }
-        None => CommentsResponse::from(comments),
+        None => comments.into_iter().map(Comment::from).collect(),
+    };
+    let mut comments: Vec<Comment> = comments;
+    for comment in comments.iter_mut().filter(|c| c.parent_id.is_none()) {
+        comment.replies_count = page.replies_counts.get(&comment.id).copied();
+    }
+    let comments = match query.format {
+        CommentsFormat::Flat => flat(comments),
+        CommentsFormat::Tree => tree(comments),
+    };
+    let response = CommentsResponse {
+        comments,
+        threads_count: total,
+        next_cursor: page.next_cursor,
     };
 
     Ok(response.into())
@@ -2,7 +2,9 @@ pub mod create;
 pub mod delete;
 pub mod get;
 pub mod responses;
+pub mod update;
 
 pub use create::create;
 pub use delete::delete;
 pub use get::get;
+pub use update::update;
@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+
 use crate::articles::responses::Author;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -6,6 +8,10 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct CommentsResponse {
     pub comments: Vec<Comment>,
+    /// The number of threads on the article, not the size of the page.
+    pub threads_count: u64,
+    /// The `cursor` to the next page, if there is one.
+    pub next_cursor: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -20,18 +26,40 @@ pub struct Comment {
     pub id: u64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Whether the body changed since the comment was posted.
+    pub edited: bool,
     pub body: String,
     pub author: Author,
+    /// The comment this one replies to, if any.
+    pub parent_id: Option<u64>,
+    /// Deleted comments with replies stay in their thread, with an empty body.
+    pub deleted: bool,
+    /// The number of ancestors of the comment, top-level comments being at depth 0.
+    #[serde(default)]
+    pub depth: u32,
+    /// Only filled in the tree format.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replies: Vec<Comment>,
+    /// For top-level comments with replies, in a list of threads: the number of replies in
+    /// the thread, however deep, including those left out of the page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replies_count: Option<u64>,
 }
 
 impl From<domain::Comment> for Comment {
     fn from(c: domain::Comment) -> Self {
         Self {
             id: c.id,
+            edited: c.edited(),
             body: c.body,
             created_at: c.created_at,
             updated_at: c.updated_at,
             author: c.author.into(),
+            parent_id: c.parent_id,
+            deleted: c.deleted,
+            depth: 0,
+            replies: vec![],
+            replies_count: None,
         }
     }
 }
@@ -40,17 +68,54 @@ impl From<domain::CommentView> for Comment {
     fn from(c: domain::CommentView) -> Self {
         Self {
             id: c.id,
+            edited: c.edited(),
             body: c.body,
             created_at: c.created_at,
             updated_at: c.updated_at,
             author: c.author.into(),
+            parent_id: c.parent_id,
+            deleted: c.deleted,
+            depth: 0,
+            replies: vec![],
+            replies_count: None,
         }
     }
 }
 
-impl<T: Into<Comment>> From<Vec<T>> for CommentsResponse {
-    fn from(v: Vec<T>) -> Self {
-        let comments: Vec<Comment> = v.into_iter().map(|c| c.into()).collect();
-        Self { comments }
+/// Threads one after the other, replies following their parent in posting order.
+pub fn flat(comments: Vec<Comment>) -> Vec<Comment> {
+    let mut flat = vec![];
+    let mut stack = tree(comments);
+    stack.reverse();
+    while let Some(mut comment) = stack.pop() {
+        let replies = std::mem::take(&mut comment.replies);
+        flat.push(comment);
+        stack.extend(replies.into_iter().rev());
+    }
+    flat
+}
+
+/// Top-level comments, with their replies nested in them. Replies, given in posting order
+/// after the top-level comments, are moved to the top-level if their parent is missing.
+pub fn tree(comments: Vec<Comment>) -> Vec<Comment> {
+    let ids: HashSet<u64> = comments.iter().map(|c| c.id).collect();
+    let mut replies: HashMap<Option<u64>, Vec<Comment>> = HashMap::new();
+    for comment in comments {
+        let parent_id = comment.parent_id.filter(|id| ids.contains(id));
+        replies.entry(parent_id).or_default().push(comment);
+    }
+    nest(&mut replies, None, 0)
+}
+
+fn nest(
+    replies: &mut HashMap<Option<u64>, Vec<Comment>>,
+    parent_id: Option<u64>,
+    depth: u32,
+) -> Vec<Comment> {
+    let mut comments = replies.remove(&parent_id).unwrap_or_default();
+    for comment in &mut comments {
+        comment.depth = depth;
+        comment.replies = nest(replies, Some(comment.id), depth + 1);
     }
+    comments
 }
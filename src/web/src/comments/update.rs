@@ -0,0 +1,41 @@
+use axum::Extension;
+use domain::{repositories::Repository, CommentContent};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Json, Path, User},
+};
+
+use super::responses::CommentResponse;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub comment: UpdateCommentRequest,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCommentRequest {
+    pub body: String,
+}
+
+pub async fn update(
+    ctx: Extension<ApplicationContext>,
+    user: User,
+    Path((slug, comment_id)): Path<(String, u64)>,
+    request: Json<Request>,
+) -> ApiResult<Json<CommentResponse>> {
+    let content = CommentContent::new(request.0.comment.body)?;
+    let author = ctx.repo().get_user_by_id(user.user_id()).await?;
+    let article = ctx.repo().get_article_by_slug(&slug).await?;
+    let comment = ctx.repo().get_comment(&article, comment_id).await?;
+    let edited_comment = author.edit_comment(comment, content, ctx.repo()).await?;
+
+    let response = CommentResponse {
+        comment: edited_comment.into(),
+    };
+    Ok(response.into())
+}
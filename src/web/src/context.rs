@@ -1,20 +1,38 @@
 use domain::repositories::Repository;
 
+use crate::auth::Jwt;
 use crate::repo::ArcRepo;
+use crate::settings::Settings;
 
 #[derive(Clone)]
 pub struct ApplicationContext {
     repo: ArcRepo,
+    settings: Settings,
+    jwt: Jwt,
 }
 
 impl ApplicationContext {
-    pub fn new(repo: impl Repository + 'static) -> Self {
+    /// # Panics
+    ///
+    /// If the keys in the authentication settings are not valid for the chosen algorithm.
+    pub fn new(repo: impl Repository + 'static, settings: Settings) -> Self {
+        let jwt = Jwt::new(&settings.authentication).expect("Invalid authentication settings");
         Self {
             repo: ArcRepo::new(repo),
+            settings,
+            jwt,
         }
     }
 
     pub fn repo(&self) -> &ArcRepo {
         &self.repo
     }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn jwt(&self) -> &Jwt {
+        &self.jwt
+    }
 }
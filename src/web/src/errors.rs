@@ -5,10 +5,13 @@ use axum::{
     Json,
 };
 use domain::{
-    ChangeArticleError, DatabaseError, DeleteCommentError, GetArticleError, GetUserError,
-    LoginError, PasswordError, PublishArticleError, SignUpError,
+    ChangeArticleError, ChangeCommentError, DatabaseError, GetArticleError, GetUserError,
+    LoginError, PasswordError, PublishArticleError, RefreshSessionError, SignUpError,
+    UpdateUserError, ValidationError,
 };
-use serde_json::json;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 
 pub type ApiResult<T> = Result<T, ApiError>;
 #[derive(thiserror::Error, Debug)]
@@ -20,7 +23,7 @@ pub enum ApiError {
     #[error(transparent)]
     Database(#[from] DatabaseError),
     #[error(transparent)]
-    DeleteComment(#[from] DeleteCommentError),
+    ChangeComment(#[from] ChangeCommentError),
     #[error(transparent)]
     GetArticle(#[from] GetArticleError),
     #[error(transparent)]
@@ -30,68 +33,181 @@ pub enum ApiError {
     #[error(transparent)]
     PublishArticle(#[from] PublishArticleError),
     #[error(transparent)]
+    RefreshSession(#[from] RefreshSessionError),
+    #[error(transparent)]
     SingUp(#[from] SignUpError),
+    #[error(transparent)]
+    UpdateUser(#[from] UpdateUserError),
+    #[error(transparent)]
+    Validation(#[from] ValidationError),
+    #[error("Missing or invalid credentials")]
+    Unauthorized,
+    /// The request could not be extracted from the body, query string or path.
+    #[error("{message}")]
+    Rejected { status: StatusCode, message: String },
+}
+
+impl ApiError {
+    /// Wraps the rejection of one of axum's extractors, keeping its status code.
+    pub fn rejected(rejection: impl IntoResponse + fmt::Display) -> Self {
+        let message = rejection.to_string();
+        ApiError::Rejected {
+            status: rejection.into_response().status(),
+            message,
+        }
+    }
+}
+
+/// A stable, machine-readable identifier of what went wrong, for clients to branch on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Some fields of the request are invalid: they are listed in `errors`.
+    InvalidInput,
+    UsernameTaken,
+    EmailTaken,
+    InvalidPassword,
+    DuplicatedSlug,
+    /// The credentials are missing, invalid or belong to a revoked session.
+    Unauthorized,
+    InvalidCredentials,
+    InvalidRefreshToken,
+    /// The user is authenticated, but not allowed to change the resource.
+    Forbidden,
+    UserNotFound,
+    ArticleNotFound,
+    CommentNotFound,
+    InternalError,
+}
+
+/// The body of every error response: `errors` follows the RealWorld format, field-level
+/// problems being keyed by field and the others by `body`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ErrorResponse {
+    pub errors: BTreeMap<String, Vec<String>>,
+    pub code: ErrorCode,
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         tracing::error!("Error on request: {}", self);
-        let (status, error_message) = match self {
-            ApiError::Login(LoginError::NotFound) => not_found(""),
-            ApiError::Login(LoginError::PasswordError(_)) => bad_request(""),
-            ApiError::Login(LoginError::DatabaseError(_)) => {
-                internal_server_error("Something went wrong")
-            }
-            ApiError::ChangeArticle(ChangeArticleError::ArticleNotFound { .. }) => not_found(""),
-            ApiError::ChangeArticle(ChangeArticleError::Forbidden { .. }) => unauthorized(""),
-            ApiError::ChangeArticle(ChangeArticleError::DatabaseError { .. }) => {
-                internal_server_error("Something went wrong")
-            }
-            ApiError::Database(_) => internal_server_error("Something went wrong"),
-            ApiError::DeleteComment(DeleteCommentError::CommentNotFound { .. }) => not_found(""),
-            ApiError::DeleteComment(DeleteCommentError::UserNotFound { .. }) => not_found(""),
-            ApiError::DeleteComment(DeleteCommentError::Forbidden { .. }) => unauthorized(""),
-            ApiError::DeleteComment(DeleteCommentError::DatabaseError { .. }) => {
-                internal_server_error("Something went wrong")
-            }
-            ApiError::GetArticle(GetArticleError::ArticleNotFound { .. }) => not_found(""),
-            ApiError::GetArticle(GetArticleError::AuthorNotFound { .. }) => not_found(""),
-            ApiError::GetArticle(GetArticleError::DatabaseError(_)) => {
-                internal_server_error("Something went wrong")
+        use ErrorCode::*;
+        let (status, code, message) = match self {
+            ApiError::SingUp(SignUpError::Invalid(e))
+            | ApiError::UpdateUser(UpdateUserError::Invalid(e))
+            | ApiError::ChangeArticle(ChangeArticleError::Invalid(e))
+            | ApiError::Validation(e) => return invalid(InvalidInput, e),
+            ApiError::SingUp(SignUpError::UsernameTaken { .. })
+            | ApiError::UpdateUser(UpdateUserError::UsernameTaken { .. }) => {
+                return invalid(UsernameTaken, already_taken("username"))
             }
-            ApiError::GetUser(GetUserError::NotFound { .. }) => not_found(""),
-            ApiError::GetUser(GetUserError::NotFoundByUsername { .. }) => not_found(""),
-            ApiError::GetUser(GetUserError::DatabaseError { .. }) => {
-                internal_server_error("Something went wrong")
+            ApiError::SingUp(SignUpError::EmailTaken { .. })
+            | ApiError::UpdateUser(UpdateUserError::EmailTaken { .. }) => {
+                return invalid(EmailTaken, already_taken("email"))
             }
-            ApiError::Password(_) => bad_request(""),
-            ApiError::PublishArticle(PublishArticleError::AuthorNotFound { .. }) => not_found(""),
-            ApiError::PublishArticle(PublishArticleError::DatabaseError { .. }) => {
-                internal_server_error("Something went wrong")
+            ApiError::Rejected { status, message } => {
+                let code = match status.is_server_error() {
+                    true => InternalError,
+                    false => InvalidInput,
+                };
+                let mut e = ValidationError::default();
+                e.add("body", message);
+                return error_response(status, code, e);
             }
-            ApiError::PublishArticle(PublishArticleError::DuplicatedSlug { .. }) => {
-                bad_request("Invalid slug")
+            ApiError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                Unauthorized,
+                "Missing or invalid credentials",
+            ),
+            // Unknown emails, wrong passwords and broken hashes look all the same
+            ApiError::Login(LoginError::InvalidCredentials | LoginError::PasswordError(_)) => (
+                StatusCode::UNAUTHORIZED,
+                InvalidCredentials,
+                "Invalid credentials",
+            ),
+            ApiError::Password(_)
+            | ApiError::SingUp(SignUpError::PasswordError(_))
+            | ApiError::UpdateUser(UpdateUserError::PasswordError(_)) => (
+                StatusCode::BAD_REQUEST,
+                InvalidPassword,
+                "The password could not be processed",
+            ),
+            ApiError::ChangeArticle(ChangeArticleError::ArticleNotFound { .. })
+            | ApiError::GetArticle(GetArticleError::ArticleNotFound { .. }) => (
+                StatusCode::NOT_FOUND,
+                ArticleNotFound,
+                "There is no such article",
+            ),
+            ApiError::ChangeArticle(ChangeArticleError::Forbidden { .. }) => (
+                StatusCode::FORBIDDEN,
+                Forbidden,
+                "Only the author can change the article",
+            ),
+            ApiError::ChangeComment(ChangeCommentError::CommentNotFound { .. }) => (
+                StatusCode::NOT_FOUND,
+                CommentNotFound,
+                "There is no such comment on the article",
+            ),
+            ApiError::ChangeComment(ChangeCommentError::Forbidden { .. }) => (
+                StatusCode::FORBIDDEN,
+                Forbidden,
+                "Only the author can change the comment",
+            ),
+            ApiError::ChangeComment(ChangeCommentError::UserNotFound { .. })
+            | ApiError::GetArticle(GetArticleError::AuthorNotFound { .. })
+            | ApiError::GetUser(GetUserError::NotFound { .. })
+            | ApiError::GetUser(GetUserError::NotFoundByUsername { .. })
+            | ApiError::PublishArticle(PublishArticleError::AuthorNotFound { .. })
+            | ApiError::UpdateUser(UpdateUserError::UserNotFound { .. }) => {
+                (StatusCode::NOT_FOUND, UserNotFound, "There is no such user")
             }
-            ApiError::SingUp(_) => internal_server_error("Something went wrong"),
+            ApiError::PublishArticle(PublishArticleError::DuplicatedSlug { .. }) => (
+                StatusCode::BAD_REQUEST,
+                DuplicatedSlug,
+                "Another article uses this slug: change the title",
+            ),
+            ApiError::RefreshSession(RefreshSessionError::InvalidRefreshToken) => (
+                StatusCode::UNAUTHORIZED,
+                InvalidRefreshToken,
+                "The refresh token is unknown, expired or revoked",
+            ),
+            ApiError::Login(LoginError::DatabaseError(_))
+            | ApiError::ChangeArticle(ChangeArticleError::DatabaseError { .. })
+            | ApiError::Database(_)
+            | ApiError::ChangeComment(ChangeCommentError::DatabaseError { .. })
+            | ApiError::GetArticle(GetArticleError::DatabaseError(_))
+            | ApiError::GetUser(GetUserError::DatabaseError { .. })
+            | ApiError::PublishArticle(PublishArticleError::DatabaseError { .. })
+            | ApiError::RefreshSession(RefreshSessionError::DatabaseError(_))
+            | ApiError::SingUp(SignUpError::DatabaseError(_))
+            | ApiError::UpdateUser(UpdateUserError::DatabaseError(_)) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                InternalError,
+                "Something went wrong",
+            ),
         };
 
-        let body = Json(json!({
-            "error": error_message,
-        }));
-
-        (status, body).into_response()
+        let mut e = ValidationError::default();
+        e.add("body", message);
+        error_response(status, code, e)
     }
 }
 
-fn internal_server_error(msg: &str) -> (StatusCode, &str) {
-    (StatusCode::INTERNAL_SERVER_ERROR, msg)
-}
-fn bad_request(msg: &str) -> (StatusCode, &str) {
-    (StatusCode::BAD_REQUEST, msg)
+fn already_taken(field: &str) -> ValidationError {
+    let mut e = ValidationError::default();
+    e.add(field, "has already been taken");
+    e
 }
-fn not_found(msg: &str) -> (StatusCode, &str) {
-    (StatusCode::NOT_FOUND, msg)
+
+/// Field-level problems, in the format expected by RealWorld clients.
+fn invalid(code: ErrorCode, e: ValidationError) -> Response {
+    error_response(StatusCode::UNPROCESSABLE_ENTITY, code, e)
 }
-fn unauthorized(msg: &str) -> (StatusCode, &str) {
-    (StatusCode::UNAUTHORIZED, msg)
+
+fn error_response(status: StatusCode, code: ErrorCode, e: ValidationError) -> Response {
+    let body = ErrorResponse {
+        errors: e.errors().clone(),
+        code,
+    };
+    (status, Json(body)).into_response()
 }
@@ -1,6 +1,14 @@
 use crate::auth::{extract_claims, Claims};
-use axum::extract::FromRequest;
-use axum::http::StatusCode;
+use crate::context::ApplicationContext;
+use crate::errors::ApiError;
+use axum::body::HttpBody;
+use axum::extract::{FromRequest, RequestParts};
+use axum::response::{IntoResponse, Response};
+use axum::BoxError;
+use domain::repositories::Repository;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::ops::Deref;
 use uuid::Uuid;
 
 pub struct User(Claims);
@@ -9,6 +17,10 @@ impl User {
     pub fn user_id(&self) -> Uuid {
         self.0.user_id()
     }
+
+    pub fn session_id(&self) -> Uuid {
+        self.0.session_id()
+    }
 }
 
 #[async_trait::async_trait]
@@ -16,13 +28,104 @@ impl<B> FromRequest<B> for User
 where
     B: Send,
 {
-    type Rejection = (StatusCode, &'static str);
-
-    async fn from_request(
-        req: &mut axum::extract::RequestParts<B>,
-    ) -> Result<Self, Self::Rejection> {
-        extract_claims(req.headers())
-            .ok_or((StatusCode::UNAUTHORIZED, "Unauthorized"))
-            .map(User)
+    type Rejection = ApiError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let ctx = req
+            .extensions()
+            .get::<ApplicationContext>()
+            .expect("ApplicationContext extension is missing");
+        let claims = extract_claims(req.headers(), ctx.jwt()).ok_or(ApiError::Unauthorized)?;
+
+        // Valid tokens are rejected as well once their session has been revoked
+        match ctx.repo().get_session(claims.session_id()).await {
+            Ok(session) if session.is_active() && session.user_id == claims.user_id() => {
+                Ok(User(claims))
+            }
+            Err(domain::GetSessionError::DatabaseError(e)) => Err(e.into()),
+            _ => Err(ApiError::Unauthorized),
+        }
+    }
+}
+
+/// [`axum::Json`], rejecting bodies it cannot parse with an [`ApiError`], so that they get
+/// the same format as the other errors.
+///
+/// It can be used as a response too.
+pub struct Json<T>(pub T);
+
+#[async_trait::async_trait]
+impl<T, B> FromRequest<B> for Json<T>
+where
+    T: DeserializeOwned,
+    B: HttpBody + Send,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let axum::Json(value) = axum::Json::from_request(req)
+            .await
+            .map_err(ApiError::rejected)?;
+        Ok(Json(value))
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for Json<T> {
+    fn from(value: T) -> Self {
+        Json(value)
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// [`axum::extract::Query`], rejecting invalid query strings with an [`ApiError`].
+pub struct Query<T>(pub T);
+
+#[async_trait::async_trait]
+impl<T, B> FromRequest<B> for Query<T>
+where
+    T: DeserializeOwned,
+    B: Send,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let axum::extract::Query(value) = axum::extract::Query::from_request(req)
+            .await
+            .map_err(ApiError::rejected)?;
+        Ok(Query(value))
+    }
+}
+
+/// [`axum::extract::Path`], rejecting invalid path parameters with an [`ApiError`].
+pub struct Path<T>(pub T);
+
+#[async_trait::async_trait]
+impl<T, B> FromRequest<B> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    B: Send,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let axum::extract::Path(value) = axum::extract::Path::from_request(req)
+            .await
+            .map_err(ApiError::rejected)?;
+        Ok(Path(value))
     }
 }
@@ -7,5 +7,6 @@ pub mod errors;
 pub mod extractor;
 pub mod profiles;
 pub mod repo;
+pub mod settings;
 pub mod users;
 pub use app::get_app;
@@ -1,8 +1,10 @@
 use crate::{
-    context::ApplicationContext, errors::ApiResult, extractor::User,
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Path, User},
     profiles::responses::ProfileResponse,
 };
-use axum::{extract::Path, Extension, Json};
+use axum::{Extension, Json};
 use domain::repositories::Repository;
 
 pub enum Action {
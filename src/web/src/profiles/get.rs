@@ -1,7 +1,11 @@
-use axum::{extract::Path, Extension, Json};
+use axum::{Extension, Json};
 use domain::repositories::Repository;
 
-use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Path, User},
+};
 
 use super::responses::ProfileResponse;
 
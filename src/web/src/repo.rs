@@ -27,6 +27,13 @@ impl Repository for ArcRepo {
         self.0.get_article_by_slug(slug).await
     }
 
+    async fn get_current_slug(
+        &self,
+        old_slug: &str,
+    ) -> Result<Option<String>, domain::DatabaseError> {
+        self.0.get_current_slug(old_slug).await
+    }
+
     async fn get_article_view(
         &self,
         viewer: &domain::User,
@@ -46,15 +53,23 @@ impl Repository for ArcRepo {
     async fn find_articles(
         &self,
         query: domain::ArticleQuery,
-    ) -> Result<Vec<domain::Article>, domain::DatabaseError> {
+    ) -> Result<domain::Page<domain::Article>, domain::DatabaseError> {
         self.0.find_articles(query).await
     }
 
+    async fn search_articles(
+        &self,
+        text: &domain::SearchText,
+        query: domain::ArticleQuery,
+    ) -> Result<domain::Page<domain::SearchHit>, domain::DatabaseError> {
+        self.0.search_articles(text, query).await
+    }
+
     async fn feed(
         &self,
         user: &domain::User,
         query: domain::FeedQuery,
-    ) -> Result<Vec<domain::ArticleView>, domain::DatabaseError> {
+    ) -> Result<domain::FeedPage, domain::DatabaseError> {
         self.0.feed(user, query).await
     }
 
@@ -67,28 +82,57 @@ impl Repository for ArcRepo {
         user: &domain::User,
         article: &domain::Article,
         comment: domain::CommentContent,
+        parent_id: Option<u64>,
     ) -> Result<domain::Comment, domain::DatabaseError> {
-        self.0.comment_article(user, article, comment).await
+        self.0
+            .comment_article(user, article, comment, parent_id)
+            .await
     }
 
     async fn get_comment(
         &self,
+        article: &domain::Article,
         comment_id: u64,
-    ) -> Result<domain::Comment, domain::DeleteCommentError> {
-        self.0.get_comment(comment_id).await
+    ) -> Result<domain::Comment, domain::ChangeCommentError> {
+        self.0.get_comment(article, comment_id).await
     }
 
     async fn get_comments(
         &self,
         article: &domain::Article,
-    ) -> Result<Vec<domain::Comment>, domain::DatabaseError> {
-        self.0.get_comments(article).await
+        query: domain::CommentQuery,
+    ) -> Result<domain::CommentPage, domain::DatabaseError> {
+        self.0.get_comments(article, query).await
+    }
+
+    async fn get_comments_views(
+        &self,
+        viewer: &domain::User,
+        comments: Vec<domain::Comment>,
+    ) -> Result<Vec<domain::CommentView>, domain::DatabaseError> {
+        self.0.get_comments_views(viewer, comments).await
     }
 
-    async fn delete_comment(&self, comment_id: u64) -> Result<(), domain::DeleteCommentError> {
+    async fn delete_comment(&self, comment_id: u64) -> Result<(), domain::ChangeCommentError> {
         self.0.delete_comment(comment_id).await
     }
 
+    async fn has_replies(&self, comment_id: u64) -> Result<bool, domain::DatabaseError> {
+        self.0.has_replies(comment_id).await
+    }
+
+    async fn tombstone_comment(&self, comment_id: u64) -> Result<(), domain::ChangeCommentError> {
+        self.0.tombstone_comment(comment_id).await
+    }
+
+    async fn update_comment(
+        &self,
+        comment_id: u64,
+        content: domain::CommentContent,
+    ) -> Result<domain::Comment, domain::ChangeCommentError> {
+        self.0.update_comment(comment_id, content).await
+    }
+
     async fn update_article(
         &self,
         article: domain::Article,
@@ -121,7 +165,7 @@ impl Repository for ArcRepo {
         &self,
         user: domain::User,
         update: domain::UserUpdate,
-    ) -> Result<domain::User, domain::DatabaseError> {
+    ) -> Result<domain::User, domain::UpdateUserError> {
         self.0.update_user(user, update).await
     }
 
@@ -168,7 +212,53 @@ impl Repository for ArcRepo {
         self.0.unfollow(follower, to_be_unfollowed).await
     }
 
-    async fn get_tags(&self) -> Result<std::collections::HashSet<String>, domain::DatabaseError> {
-        self.0.get_tags().await
+    async fn get_tags(
+        &self,
+        limit: Option<u64>,
+    ) -> Result<Vec<domain::TagCount>, domain::DatabaseError> {
+        self.0.get_tags(limit).await
+    }
+
+    async fn create_session(
+        &self,
+        user: &domain::User,
+        refresh_token: &domain::RefreshToken,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<domain::Session, domain::DatabaseError> {
+        self.0.create_session(user, refresh_token, expires_at).await
+    }
+
+    async fn get_session(
+        &self,
+        session_id: uuid::Uuid,
+    ) -> Result<domain::Session, domain::GetSessionError> {
+        self.0.get_session(session_id).await
+    }
+
+    async fn get_session_by_refresh_token(
+        &self,
+        refresh_token: &domain::RefreshToken,
+    ) -> Result<domain::Session, domain::GetSessionError> {
+        self.0.get_session_by_refresh_token(refresh_token).await
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        session: &domain::Session,
+        current: &domain::RefreshToken,
+        refresh_token: &domain::RefreshToken,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<domain::Session, domain::RefreshSessionError> {
+        self.0
+            .rotate_refresh_token(session, current, refresh_token, expires_at)
+            .await
+    }
+
+    async fn revoke_session(&self, session_id: uuid::Uuid) -> Result<(), domain::DatabaseError> {
+        self.0.revoke_session(session_id).await
+    }
+
+    async fn revoke_sessions(&self, user: &domain::User) -> Result<(), domain::DatabaseError> {
+        self.0.revoke_sessions(user).await
     }
 }
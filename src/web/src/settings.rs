@@ -0,0 +1,97 @@
+//! Settings tweaking the behaviour of the web application.
+//!
+//! They are populated by the `application` crate from its configuration files.
+use std::time::Duration;
+
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Default)]
+pub struct Settings {
+    pub pagination: Pagination,
+    pub authentication: Authentication,
+    pub articles: Articles,
+}
+
+/// How the JWTs handed out to users are signed and verified.
+#[derive(Clone, Debug)]
+pub struct Authentication {
+    pub algorithm: Algorithm,
+    /// Secret (HS256) or PEM encoded private key (RS256, EdDSA) used to sign new tokens.
+    pub signing_key: String,
+    /// Secrets (HS256) or PEM encoded public keys (RS256, EdDSA) accepted when verifying a token.
+    ///
+    /// The key matching `signing_key` must be part of the list: keeping the previous ones
+    /// around after a rotation keeps the tokens they signed valid until they expire.
+    pub verification_keys: Vec<String>,
+    /// Value of the `iss` claim, checked when verifying tokens if set.
+    pub issuer: Option<String>,
+    /// Value of the `aud` claim, checked when verifying tokens if set.
+    pub audience: Option<String>,
+    /// How long a token is valid after being issued.
+    pub token_lifetime: Duration,
+    /// How long a refresh token can be exchanged for new access tokens.
+    pub session_lifetime: Duration,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum Algorithm {
+    HS256,
+    RS256,
+    EdDSA,
+}
+
+impl Default for Authentication {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            signing_key: "secret".to_string(),
+            verification_keys: vec!["secret".to_string()],
+            issuer: None,
+            audience: None,
+            token_lifetime: Duration::from_secs(3600),
+            session_lifetime: Duration::from_secs(30 * 24 * 3600),
+        }
+    }
+}
+
+/// Page sizes used by the endpoints returning a list of articles.
+#[derive(Clone, Copy, Debug)]
+pub struct Pagination {
+    /// Number of items returned when the client does not specify a `limit`.
+    pub default_limit: u64,
+    /// Upper bound to the `limit` a client can ask for.
+    pub max_limit: u64,
+}
+
+impl Pagination {
+    /// Returns the page size to use given the one requested by the client, if any.
+    pub fn limit(&self, requested: Option<u64>) -> u64 {
+        requested.unwrap_or(self.default_limit).min(self.max_limit)
+    }
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            default_limit: 20,
+            max_limit: 100,
+        }
+    }
+}
+
+/// How articles are handled when they change.
+#[derive(Clone, Copy, Debug)]
+pub struct Articles {
+    /// Whether renaming an article moves it to a slug matching its new title.
+    ///
+    /// Old slugs redirect to the new one.
+    pub slug_follows_title: bool,
+}
+
+impl Default for Articles {
+    fn default() -> Self {
+        Self {
+            slug_follows_title: true,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use axum::{Extension, Json};
 use domain::repositories::Repository;
 
-use crate::{auth::encode_token, context::ApplicationContext, errors::ApiResult, extractor::User};
+use crate::{context::ApplicationContext, errors::ApiResult, extractor::User};
 
 use super::responses::UserResponse;
 
@@ -9,8 +9,9 @@ pub async fn get_current_user(
     ctx: Extension<ApplicationContext>,
     user: User,
 ) -> ApiResult<Json<UserResponse>> {
+    let session_id = user.session_id();
     let user = ctx.repo().get_user_by_id(user.user_id()).await?;
-    let token = encode_token(user.id);
+    let token = ctx.jwt().encode_token(user.id, session_id);
 
     let payload: UserResponse = (user, token).into();
 
@@ -1,10 +1,11 @@
-use axum::{Extension, Json};
+use axum::Extension;
 use serde::Deserialize;
 
-use crate::{auth::encode_token, context::ApplicationContext, errors::ApiResult};
+use crate::{context::ApplicationContext, errors::ApiResult, extractor::Json};
 use domain::repositories::Repository;
 
 use super::responses::UserResponse;
+use super::sessions::start_session;
 
 #[derive(Deserialize)]
 pub struct AuthRequest {
@@ -25,9 +26,6 @@ pub async fn login(
         .repo()
         .get_user_by_email_and_password(&request.user.email, &request.user.password)
         .await?;
-    let token = encode_token(logged_in_user.id);
 
-    let response = UserResponse::from((logged_in_user, token));
-
-    Ok(response.into())
+    Ok(start_session(&ctx, logged_in_user).await?.into())
 }
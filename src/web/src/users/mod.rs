@@ -2,9 +2,11 @@ pub mod current_user;
 pub mod login;
 pub mod register;
 pub mod responses;
+pub mod sessions;
 pub mod update;
 
 pub use current_user::get_current_user;
 pub use login::login;
 pub use register::register;
+pub use sessions::{logout, logout_all, refresh_token};
 pub use update::update_user;
@@ -1,11 +1,12 @@
-use axum::{Extension, Json};
+use axum::Extension;
 use domain::{repositories::Repository, SignUp};
 use serde::Deserialize;
 use std::convert::{TryFrom, TryInto};
 
-use crate::{auth::encode_token, context::ApplicationContext, errors::ApiResult};
+use crate::{context::ApplicationContext, errors::ApiResult, extractor::Json};
 
 use super::responses::UserResponse;
+use super::sessions::start_session;
 
 #[derive(Deserialize, Debug)]
 pub struct RegistrationRequest {
@@ -20,15 +21,10 @@ pub struct NewUserRequest {
 }
 
 impl TryFrom<RegistrationRequest> for SignUp {
-    type Error = domain::PasswordError;
+    type Error = domain::SignUpError;
 
     fn try_from(r: RegistrationRequest) -> Result<Self, Self::Error> {
-        let sign_up = Self {
-            username: r.user.username,
-            password: domain::Password::from_clear_text(r.user.password)?,
-            email: r.user.email,
-        };
-        Ok(sign_up)
+        Self::new(r.user.username, r.user.email, &r.user.password)
     }
 }
 
@@ -38,7 +34,6 @@ pub async fn register(
 ) -> ApiResult<Json<UserResponse>> {
     let sign_up: SignUp = request.0.try_into()?;
     let new_user = ctx.repo().sign_up(sign_up).await?;
-    let token = encode_token(new_user.id);
 
-    Ok(UserResponse::from((new_user, token)).into())
+    Ok(start_session(&ctx, new_user).await?.into())
 }
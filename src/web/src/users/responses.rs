@@ -10,6 +10,13 @@ pub struct User {
     pub username: String,
     pub email: String,
     pub token: String,
+    /// Only handed out when a new session starts or is refreshed.
+    #[serde(
+        rename = "refreshToken",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub refresh_token: Option<String>,
     pub bio: Option<String>,
     pub image: Option<String>,
 }
@@ -22,9 +29,17 @@ impl From<(domain::User, String)> for UserResponse {
                 username: u.profile.username,
                 email: u.email,
                 token,
+                refresh_token: None,
                 bio: u.profile.bio,
                 image: u.profile.image,
             },
         }
     }
 }
+
+impl UserResponse {
+    pub fn with_refresh_token(mut self, refresh_token: &domain::RefreshToken) -> Self {
+        self.user.refresh_token = Some(refresh_token.as_str().to_string());
+        self
+    }
+}
@@ -0,0 +1,62 @@
+use axum::Extension;
+use domain::repositories::Repository;
+use domain::{RefreshToken, Session};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::ApplicationContext,
+    errors::ApiResult,
+    extractor::{Json, User},
+};
+
+use super::responses::UserResponse;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RefreshRequest {
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: String,
+}
+
+/// Opens a new session for `user`: the response carries both an access and a refresh token.
+pub(crate) async fn start_session(
+    ctx: &ApplicationContext,
+    user: domain::User,
+) -> ApiResult<UserResponse> {
+    let (session, refresh_token) = Session::start(&user, session_lifetime(ctx), ctx.repo()).await?;
+    let token = ctx.jwt().encode_token(user.id, session.id);
+
+    Ok(UserResponse::from((user, token)).with_refresh_token(&refresh_token))
+}
+
+pub async fn refresh_token(
+    ctx: Extension<ApplicationContext>,
+    request: Json<RefreshRequest>,
+) -> ApiResult<Json<UserResponse>> {
+    let refresh_token = RefreshToken::from_clear_text(request.0.refresh_token);
+    let (session, refresh_token) =
+        Session::refresh(&refresh_token, session_lifetime(&ctx), ctx.repo()).await?;
+    let user = ctx.repo().get_user_by_id(session.user_id).await?;
+    let token = ctx.jwt().encode_token(user.id, session.id);
+
+    Ok(UserResponse::from((user, token))
+        .with_refresh_token(&refresh_token)
+        .into())
+}
+
+pub async fn logout(ctx: Extension<ApplicationContext>, user: User) -> ApiResult<()> {
+    ctx.repo().revoke_session(user.session_id()).await?;
+
+    Ok(())
+}
+
+pub async fn logout_all(ctx: Extension<ApplicationContext>, user: User) -> ApiResult<()> {
+    let user = ctx.repo().get_user_by_id(user.user_id()).await?;
+    user.log_out_everywhere(ctx.repo()).await?;
+
+    Ok(())
+}
+
+fn session_lifetime(ctx: &ApplicationContext) -> chrono::Duration {
+    chrono::Duration::from_std(ctx.settings().authentication.session_lifetime)
+        .expect("Session lifetime is out of range")
+}
@@ -1,10 +1,10 @@
-use axum::{Extension, Json};
+use axum::Extension;
 use serde::{Deserialize, Serialize};
 
+use crate::context::ApplicationContext;
 use crate::errors::ApiResult;
-use crate::extractor::User;
+use crate::extractor::{Json, User};
 use crate::users::responses::UserResponse;
-use crate::{auth::encode_token, context::ApplicationContext};
 use domain::repositories::Repository;
 use std::convert::{TryFrom, TryInto};
 
@@ -23,20 +23,10 @@ pub struct UpdateUserRequest {
 }
 
 impl TryFrom<UpdateUserRequest> for domain::UserUpdate {
-    type Error = domain::PasswordError;
+    type Error = domain::UpdateUserError;
 
     fn try_from(u: UpdateUserRequest) -> Result<Self, Self::Error> {
-        let update = Self {
-            email: u.email,
-            username: u.username,
-            password: u
-                .password
-                .map(domain::Password::from_clear_text)
-                .transpose()?,
-            image: u.image,
-            bio: u.bio,
-        };
-        Ok(update)
+        Self::new(u.email, u.username, u.password.as_deref(), u.image, u.bio)
     }
 }
 
@@ -45,9 +35,10 @@ pub async fn update_user(
     user: User,
     request: Json<Request>,
 ) -> ApiResult<Json<UserResponse>> {
+    let session_id = user.session_id();
     let user = ctx.repo().get_user_by_id(user.user_id()).await?;
     let updated_user = user.update(request.0.user.try_into()?, ctx.repo()).await?;
-    let token = encode_token(updated_user.id);
+    let token = ctx.jwt().encode_token(updated_user.id, session_id);
 
     let response = UserResponse::from((updated_user, token));
 
@@ -16,7 +16,8 @@ pub fn article_content() -> domain::ArticleContent {
 }
 
 pub fn new_user() -> (domain::SignUp, String) {
-    let password = fake!(Lorem.word).to_string();
+    // Long enough to pass validation
+    let password = format!("{}-password", fake!(Lorem.word));
     let sign_up = domain::SignUp {
         username: fake!(Internet.user_name).to_string(),
         email: fake!(Internet.free_email).to_string(),
@@ -14,6 +14,15 @@ use futures::FutureExt;
 
 use self::test_db::Db;
 
+/// Starts a session for `user`, returning an access token accepted by a
+/// [`test_server::TestApp`] using the default settings.
+pub async fn encode_token(db: &Db, user: &domain::User) -> String {
+    let (session, _) = domain::Session::start(user, chrono::Duration::days(1), &db.0)
+        .await
+        .unwrap();
+    realworld_web::auth::Jwt::default().encode_token(user.id, session.id)
+}
+
 pub async fn create_users(db: &Db, num_users: i32) -> Vec<(domain::User, String)> {
     let users: Vec<BoxFuture<_>> = (0..num_users).map(|_| create_user(db).boxed()).collect();
 
@@ -31,7 +40,7 @@ pub async fn create_user(db: &Db) -> (domain::User, String) {
 pub async fn create_articles(repo: &Db, users: Vec<domain::User>) -> Vec<domain::Article> {
     let articles = users
         .iter()
-        .map(|user| create_article(repo, &user).boxed())
+        .map(|user| create_article(repo, user).boxed())
         .collect::<Vec<_>>();
 
     join_all(articles).await
@@ -52,11 +61,7 @@ pub async fn create_users2(repo: &Repository, num_users: i32) -> Vec<(domain::Us
 
 pub async fn create_user2(repo: &Repository) -> (domain::User, String) {
     let (new_user, password) = generate::new_user();
-    let new_user = repo
-        .sign_up(new_user)
-        .await
-        .expect("Failed to create user")
-        .into();
+    let new_user = repo.sign_up(new_user).await.expect("Failed to create user");
     (new_user, password)
 }
 
@@ -7,9 +7,12 @@ use axum::Router;
 use realworld_web::get_app;
 use realworld_web::users::responses::UserResponse;
 
-use domain::articles::ArticleQuery;
+use domain::articles::{ArticleQuery, ArticleSort, TagMatch};
 use domain::SignUp;
+use realworld_web::articles::feed::{FeedQuery, FeedResponse};
 use realworld_web::articles::responses::{ArticleResponse, ArticlesResponse};
+use realworld_web::articles::search::SearchResponse;
+use realworld_web::articles::tags::TagsResponse;
 use realworld_web::comments::responses::{CommentResponse, CommentsResponse};
 use realworld_web::profiles::responses::ProfileResponse;
 use serde::de::DeserializeOwned;
@@ -18,9 +21,11 @@ use tower::ServiceExt;
 
 use super::test_db::Db;
 
-pub struct TestApp {
+/// A router under test together with the repository behind it. Postgres by
+/// default; see [`TestApp::in_memory`] for one that needs no database.
+pub struct TestApp<R = Db> {
     pub server: Router,
-    pub repository: Db,
+    pub repository: R,
 }
 pub async fn test_db(name: &str) -> Db {
     let settings = Settings::new(PathBuf::from("../../")).expect("Failed to load configuration");
@@ -29,14 +34,33 @@ pub async fn test_db(name: &str) -> Db {
 
 impl TestApp {
     pub async fn create(name: &str) -> Self {
+        Self::create_with_settings(name, Default::default()).await
+    }
+
+    pub async fn create_with_settings(
+        name: &str,
+        settings: realworld_web::settings::Settings,
+    ) -> Self {
         let db = test_db(name).await;
-        let app = get_app(db.0.clone());
+        let app = get_app(db.0.clone(), settings);
         Self {
             server: app,
             repository: db,
         }
     }
+}
+
+impl TestApp<realworld_memory::Repository> {
+    pub fn in_memory() -> Self {
+        let repository = realworld_memory::Repository::new();
+        Self {
+            server: get_app(repository.clone(), Default::default()),
+            repository,
+        }
+    }
+}
 
+impl<R> TestApp<R> {
     pub async fn register_user(
         &mut self,
         user: &SignUp,
@@ -96,6 +120,54 @@ impl TestApp {
         response_json_if_success(response).await
     }
 
+    pub async fn refresh_token(&mut self, refresh_token: &str) -> Result<UserResponse, Response> {
+        let response = self
+            .server
+            .clone()
+            .oneshot(
+                Request::post("/api/users/token/refresh")
+                    .header("Content-Type", "application/json")
+                    .body(
+                        json!({ "refreshToken": refresh_token })
+                            .to_string()
+                            .into_bytes()
+                            .into(),
+                    )
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response_json_if_success(response).await
+    }
+
+    pub async fn logout(&mut self, token: &str) -> Result<(), Response> {
+        self.post_without_body("/api/users/logout", token).await
+    }
+
+    pub async fn logout_all(&mut self, token: &str) -> Result<(), Response> {
+        self.post_without_body("/api/users/logout-all", token).await
+    }
+
+    async fn post_without_body(&mut self, url: &str, token: &str) -> Result<(), Response> {
+        let auth_header = format!("token: {}", token);
+        let response = self
+            .server
+            .clone()
+            .oneshot(
+                Request::post(url)
+                    .header("Authorization", auth_header)
+                    .body("".into())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(response)
+        }
+    }
+
     pub async fn get_current_user(&mut self, token: &String) -> Result<UserResponse, Response> {
         let auth_header = format!("token: {}", token);
         let response = self
@@ -183,7 +255,7 @@ impl TestApp {
         &mut self,
         query: Option<ArticleQuery>,
     ) -> Result<ArticlesResponse, Response> {
-        let query_string = serde_qs::to_string(&query).unwrap();
+        let query_string = articles_query_string(&query.unwrap_or_default());
         let url = format!("/api/articles?{}", query_string);
         let response = self
             .server
@@ -194,6 +266,52 @@ impl TestApp {
         response_json_if_success(response).await
     }
 
+    pub async fn search_articles(
+        &mut self,
+        query_string: &str,
+    ) -> Result<SearchResponse, Response> {
+        let url = format!("/api/articles/search?{}", query_string);
+        let response = self
+            .server
+            .clone()
+            .oneshot(Request::get(url).body("".into()).unwrap())
+            .await
+            .unwrap();
+        response_json_if_success(response).await
+    }
+
+    pub async fn get_tags(&mut self, query_string: &str) -> Result<TagsResponse, Response> {
+        let url = format!("/api/tags?{}", query_string);
+        let response = self
+            .server
+            .clone()
+            .oneshot(Request::get(url).body("".into()).unwrap())
+            .await
+            .unwrap();
+        response_json_if_success(response).await
+    }
+
+    pub async fn get_feed(
+        &mut self,
+        query: FeedQuery,
+        token: &str,
+    ) -> Result<FeedResponse, Response> {
+        let query_string = serde_qs::to_string(&query).unwrap();
+        let url = format!("/api/articles/feed?{}", query_string);
+        let response = self
+            .server
+            .clone()
+            .oneshot(
+                Request::get(url)
+                    .header("Authorization", format!("token: {}", token))
+                    .body("".into())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response_json_if_success(response).await
+    }
+
     pub async fn get_article(
         &mut self,
         slug: &str,
@@ -359,6 +477,21 @@ impl TestApp {
         response_json_if_success(response).await
     }
 
+    pub async fn query_comments(
+        &mut self,
+        slug: &str,
+        query_string: &str,
+    ) -> Result<CommentsResponse, Response> {
+        let url = format!("/api/articles/{}/comments?{}", slug, query_string);
+        let response = self
+            .server
+            .clone()
+            .oneshot(Request::get(url).body("".into()).unwrap())
+            .await
+            .unwrap();
+        response_json_if_success(response).await
+    }
+
     pub async fn create_comment(
         &mut self,
         slug: &str,
@@ -383,6 +516,31 @@ impl TestApp {
         response_json_if_success(response).await
     }
 
+    pub async fn update_comment(
+        &mut self,
+        slug: &str,
+        comment_id: &u64,
+        comment: &realworld_web::comments::update::Request,
+        token: &str,
+    ) -> Result<CommentResponse, Response> {
+        let url = format!("/api/articles/{}/comments/{}", slug, comment_id);
+        let auth_header = format!("token: {}", token);
+        let body = serde_json::to_string(comment).unwrap();
+        let response = self
+            .server
+            .clone()
+            .oneshot(
+                Request::put(url)
+                    .header("Authorization", auth_header)
+                    .header("Content-Type", "application/json")
+                    .body(body.into_bytes().into())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response_json_if_success(response).await
+    }
+
     pub async fn delete_comment(
         &mut self,
         slug: &str,
@@ -408,12 +566,45 @@ impl TestApp {
             Err(response)
         }
     }
+
+    /// Sends a request as is, e.g. a malformed one no other helper can build.
+    pub async fn send(&mut self, request: Request<axum::body::Body>) -> Response {
+        self.server.clone().oneshot(request).await.unwrap()
+    }
 }
 
-impl std::ops::Drop for TestApp {
+impl<R> std::ops::Drop for TestApp<R> {
     fn drop(&mut self) {}
 }
 
+/// Tags are sent as repeated `tag` parameters, which `serde_qs` cannot produce.
+fn articles_query_string(query: &ArticleQuery) -> String {
+    let mut params = vec![];
+    if let Some(author) = &query.author {
+        params.push(("author", author.clone()));
+    }
+    if let Some(favorited) = &query.favorited {
+        params.push(("favorited", favorited.clone()));
+    }
+    for tag in &query.tags {
+        params.push(("tag", tag.clone()));
+    }
+    if query.tag_match == TagMatch::All {
+        params.push(("tagMatch", "all".to_string()));
+    }
+    if query.sort != ArticleSort::Recent {
+        let sort = serde_json::to_value(query.sort).unwrap();
+        params.push(("sort", sort.as_str().unwrap().to_string()));
+    }
+    if let Some(limit) = query.limit {
+        params.push(("limit", limit.to_string()));
+    }
+    if let Some(offset) = query.offset {
+        params.push(("offset", offset.to_string()));
+    }
+    serde_urlencoded::to_string(params).unwrap()
+}
+
 pub async fn response_json_if_success<T: DeserializeOwned>(
     response: Response,
 ) -> Result<T, Response> {
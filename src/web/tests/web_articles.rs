@@ -2,16 +2,24 @@
 
 mod helpers;
 
+use helpers::encode_token;
 use helpers::generate;
-use helpers::test_server::TestApp;
+use helpers::test_server::{response_json, TestApp};
 use helpers::{create_article, create_articles, create_user, create_users};
 
-use domain::articles::ArticleQuery;
+use domain::repositories::Repository;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use domain::articles::{ArticleQuery, ArticleSort, TagMatch};
 use fake::fake;
 use itertools::Itertools;
+use realworld_web::articles::feed::FeedQuery;
 use realworld_web::articles::insert::NewArticleRequest;
+use realworld_web::articles::tags::TagResponse;
 use realworld_web::articles::update::UpdateArticleRequest;
-use realworld_web::auth::encode_token;
+use realworld_web::errors::{ErrorCode, ErrorResponse};
+use realworld_web::settings::{Articles, Pagination, Settings};
 
 #[tokio::test]
 async fn should_list_articles() {
@@ -45,7 +53,7 @@ async fn favorite_count_is_updated_correctly() {
     assert_eq!(article.favorites_count, 0);
 
     for (i, user) in users.iter().enumerate() {
-        let token = encode_token(user.id);
+        let token = encode_token(&server.repository, user).await;
         server.favorite_article(&slug, &token).await.unwrap();
 
         let a = server
@@ -58,7 +66,7 @@ async fn favorite_count_is_updated_correctly() {
     }
 
     for user in &users {
-        let token = encode_token(user.id);
+        let token = encode_token(&server.repository, user).await;
         server.unfavorite_article(&slug, &token).await.unwrap();
 
         let a = server
@@ -86,8 +94,8 @@ async fn should_get_articles_by_author() {
     let author = users[0].clone();
     let query = ArticleQuery {
         author: Some(author.profile.username),
-        tag: None,
         favorited: None,
+        ..Default::default()
     };
     let articles = server.get_articles(Some(query)).await.unwrap().articles;
 
@@ -99,6 +107,173 @@ async fn should_get_articles_by_author() {
     assert_ne!(retrieved_article.slug, "");
 }
 
+#[tokio::test]
+async fn should_sort_articles() {
+    let mut server = TestApp::create("should_sort_articles").await;
+    let user = create_user(&server.repository).await.0;
+    let token = encode_token(&server.repository, &user).await;
+    let favorite = create_article(&server.repository, &user).await.slug;
+    let recent = create_article(&server.repository, &user).await.slug;
+    server.favorite_article(&favorite, &token).await.unwrap();
+
+    let recent_first = [recent.clone(), favorite.clone()];
+    let favorite_first = [favorite, recent];
+    assert_eq!(
+        get_sorted(&mut server, ArticleSort::Recent).await,
+        recent_first
+    );
+    assert_eq!(
+        get_sorted(&mut server, ArticleSort::Oldest).await,
+        favorite_first
+    );
+    assert_eq!(
+        get_sorted(&mut server, ArticleSort::MostFavorited).await,
+        favorite_first
+    );
+}
+
+async fn get_sorted(server: &mut TestApp, sort: ArticleSort) -> Vec<String> {
+    let query = ArticleQuery {
+        sort,
+        ..Default::default()
+    };
+    let articles = server.get_articles(Some(query)).await.unwrap().articles;
+    articles.into_iter().map(|a| a.slug).collect()
+}
+
+#[tokio::test]
+async fn should_get_articles_favorited_by_user() {
+    let mut server = TestApp::create("should_get_articles_favorited_by_user").await;
+    let users = create_users(&server.repository, 3)
+        .await
+        .into_iter()
+        .map(|(u, _)| u)
+        .collect_vec();
+    let (author, other_author, fan) = (&users[0], &users[1], &users[2]);
+
+    let mut draft = generate::article_content();
+    draft.tag_list = vec!["favorite-tag".to_string()];
+    let tagged = server
+        .repository
+        .0
+        .publish_article(draft, author)
+        .await
+        .unwrap();
+    let untagged = create_article(&server.repository, author).await;
+    let other = create_article(&server.repository, other_author).await;
+    // Not favorited by anybody
+    create_article(&server.repository, author).await;
+
+    let token = encode_token(&server.repository, fan).await;
+    for article in [&tagged, &untagged, &other] {
+        server
+            .favorite_article(&article.slug, &token)
+            .await
+            .unwrap();
+    }
+
+    let query = ArticleQuery {
+        favorited: Some(fan.profile.username.clone()),
+        ..Default::default()
+    };
+    let response = server.get_articles(Some(query)).await.unwrap();
+    assert_eq!(response.articles_count, 3);
+    let slugs = response.articles.into_iter().map(|a| a.slug).collect_vec();
+    assert_eq!(
+        slugs,
+        vec![
+            other.slug.clone(),
+            untagged.slug.clone(),
+            tagged.slug.clone()
+        ]
+    );
+
+    let query = ArticleQuery {
+        author: Some(author.profile.username.clone()),
+        favorited: Some(fan.profile.username.clone()),
+        ..Default::default()
+    };
+    let response = server.get_articles(Some(query)).await.unwrap();
+    assert_eq!(response.articles_count, 2);
+    let slugs = response.articles.into_iter().map(|a| a.slug).collect_vec();
+    assert_eq!(slugs, vec![untagged.slug, tagged.slug.clone()]);
+
+    let query = ArticleQuery {
+        author: Some(author.profile.username.clone()),
+        tags: vec!["favorite-tag".to_string()],
+        favorited: Some(fan.profile.username.clone()),
+        ..Default::default()
+    };
+    let response = server.get_articles(Some(query)).await.unwrap();
+    let slugs = response.articles.into_iter().map(|a| a.slug).collect_vec();
+    assert_eq!(slugs, vec![tagged.slug]);
+
+    let query = ArticleQuery {
+        favorited: Some(author.profile.username.clone()),
+        ..Default::default()
+    };
+    let response = server.get_articles(Some(query)).await.unwrap();
+    assert!(response.articles.is_empty());
+    assert_eq!(response.articles_count, 0);
+
+    let query = ArticleQuery {
+        favorited: Some("nobody".to_string()),
+        ..Default::default()
+    };
+    let response = server.get_articles(Some(query)).await.unwrap();
+    assert!(response.articles.is_empty());
+}
+
+async fn get_tagged(server: &mut TestApp, tags: &[&str], tag_match: TagMatch) -> Vec<String> {
+    let query = ArticleQuery {
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        tag_match,
+        ..Default::default()
+    };
+    let response = server.get_articles(Some(query)).await.unwrap();
+    response
+        .articles
+        .into_iter()
+        .map(|a| a.slug)
+        .sorted()
+        .collect_vec()
+}
+
+#[tokio::test]
+async fn should_get_articles_by_tags() {
+    let mut server = TestApp::create("should_get_articles_by_tags").await;
+    let author = create_user(&server.repository).await.0;
+    let tag_sets = [
+        vec!["rust"],
+        vec!["rust", "axum"],
+        vec!["it's \"quoted\"", "a&b=c#d"],
+    ];
+    let mut slugs = vec![];
+    for tags in tag_sets {
+        let mut draft = generate::article_content();
+        draft.tag_list = tags.into_iter().map(String::from).collect();
+        let article = server
+            .repository
+            .0
+            .publish_article(draft, &author)
+            .await
+            .unwrap();
+        slugs.push(article.slug);
+    }
+
+    let any = get_tagged(&mut server, &["rust", "axum"], TagMatch::Any).await;
+    assert_eq!(any, slugs[..2].iter().cloned().sorted().collect_vec());
+    let all = get_tagged(&mut server, &["rust", "axum"], TagMatch::All).await;
+    assert_eq!(all, vec![slugs[1].clone()]);
+    let quoted = get_tagged(&mut server, &["it's \"quoted\""], TagMatch::Any).await;
+    assert_eq!(quoted, vec![slugs[2].clone()]);
+    let reserved = get_tagged(&mut server, &["a&b=c#d", "it's \"quoted\""], TagMatch::All).await;
+    assert_eq!(reserved, vec![slugs[2].clone()]);
+    assert!(get_tagged(&mut server, &["a"], TagMatch::Any)
+        .await
+        .is_empty());
+}
+
 #[tokio::test]
 async fn should_create_article() {
     let mut server = TestApp::create("should_create_article").await;
@@ -126,8 +301,8 @@ async fn should_create_article() {
 
     let query = Some(ArticleQuery {
         author: Some(user.username),
-        tag: None,
         favorited: None,
+        ..Default::default()
     });
     let articles = server.get_articles(query).await.unwrap().articles;
 
@@ -143,11 +318,14 @@ async fn should_create_article() {
 async fn should_update_article() {
     let mut server = TestApp::create("should_update_article").await;
     let user = create_user(&server.repository).await.0;
-    let token = encode_token(user.id);
+    let token = encode_token(&server.repository, &user).await;
     let article = create_article(&server.repository, &user).await;
 
     let update = realworld_web::articles::update::Request {
         article: UpdateArticleRequest {
+            tag_list: None,
+            add_tags: vec![],
+            remove_tags: vec![],
             title: Some(fake!(Lorem.sentence(4, 10)).to_string()),
             description: None,
             body: Some(fake!(Lorem.paragraph(10, 5))),
@@ -165,11 +343,204 @@ async fn should_update_article() {
     assert_eq!(update.article.body, updated_article.article.body.into());
 }
 
+#[tokio::test]
+async fn should_list_tags_by_popularity() {
+    let mut server = TestApp::create("should_list_tags_by_popularity").await;
+    let user = create_user(&server.repository).await.0;
+    for tags in [vec!["web", "rust"], vec!["rust"], vec!["db", "web", "rust"]] {
+        let mut draft = generate::article_content();
+        draft.tag_list = tags.into_iter().map(String::from).collect();
+        server
+            .repository
+            .0
+            .publish_article(draft, &user)
+            .await
+            .unwrap();
+    }
+
+    let tags = server.get_tags("").await.unwrap().tags;
+    assert_eq!(
+        tags,
+        ["rust", "web", "db"].map(|t| TagResponse::Name(t.to_string()))
+    );
+
+    let tags = server
+        .get_tags("withCounts=true&limit=2")
+        .await
+        .unwrap()
+        .tags;
+    assert_eq!(
+        tags,
+        [("rust", 3), ("web", 2)].map(|(tag, count)| TagResponse::WithCount {
+            tag: tag.to_string(),
+            count
+        })
+    );
+}
+
+#[tokio::test]
+async fn should_search_articles() {
+    let mut server = TestApp::create("should_search_articles").await;
+    let user = create_user(&server.repository).await.0;
+    let mut draft = generate::article_content();
+    draft.body = format!("{} Rust ownership explained.", draft.body);
+    draft.tag_list = vec!["rust".to_string()];
+    let article = server
+        .repository
+        .0
+        .publish_article(draft, &user)
+        .await
+        .unwrap();
+    create_article(&server.repository, &user).await;
+
+    let response = server
+        .search_articles("q=ownership&tag=rust")
+        .await
+        .unwrap();
+    assert_eq!(response.articles_count, 1);
+    assert_eq!(response.articles[0].article.slug, article.slug);
+    assert!(response.articles[0]
+        .snippet
+        .contains("<mark>ownership</mark>"));
+
+    let response = server.search_articles("q=ownership&tag=go").await.unwrap();
+    assert_eq!(response.articles_count, 0);
+
+    let response = server.search_articles("q=%20").await.err().unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn should_update_article_tags() {
+    let mut server = TestApp::create("should_update_article_tags").await;
+    let user = create_user(&server.repository).await.0;
+    let token = encode_token(&server.repository, &user).await;
+    let article = create_article(&server.repository, &user).await;
+
+    let update = realworld_web::articles::update::Request {
+        article: UpdateArticleRequest {
+            title: None,
+            description: None,
+            body: None,
+            tag_list: Some(vec!["Rust".to_string(), "web ".to_string()]),
+            add_tags: vec!["axum".to_string()],
+            remove_tags: vec!["WEB".to_string()],
+        },
+    };
+    let updated_article = server
+        .update_article(&update, &article.slug, &token)
+        .await
+        .unwrap();
+    assert_eq!(updated_article.article.tag_list, ["axum", "rust"]);
+    assert!(updated_article.article.updated_at > updated_article.article.created_at);
+}
+
+#[tokio::test]
+async fn old_slugs_redirect_to_renamed_articles() {
+    let mut server = TestApp::create("old_slugs_redirect_to_renamed_articles").await;
+    let user = create_user(&server.repository).await.0;
+    let token = encode_token(&server.repository, &user).await;
+    let article = create_article(&server.repository, &user).await;
+
+    let update = realworld_web::articles::update::Request {
+        article: UpdateArticleRequest {
+            tag_list: None,
+            add_tags: vec![],
+            remove_tags: vec![],
+            title: Some("A brand new title".to_string()),
+            description: None,
+            body: None,
+        },
+    };
+    let updated_article = server
+        .update_article(&update, &article.slug, &token)
+        .await
+        .unwrap();
+    assert_eq!(updated_article.article.slug, "a-brand-new-title");
+
+    let response = server.get_article(&article.slug, None).await.err().unwrap();
+    assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+    assert_eq!(
+        response.headers()[header::LOCATION],
+        "/api/articles/a-brand-new-title"
+    );
+    let current = server.get_article("a-brand-new-title", None).await.unwrap();
+    assert_eq!(current.article.title, "A brand new title");
+}
+
+#[tokio::test]
+async fn slugs_can_be_kept_when_titles_change() {
+    let settings = Settings {
+        articles: Articles {
+            slug_follows_title: false,
+        },
+        ..Default::default()
+    };
+    let mut server =
+        TestApp::create_with_settings("slugs_can_be_kept_when_titles_change", settings).await;
+    let user = create_user(&server.repository).await.0;
+    let token = encode_token(&server.repository, &user).await;
+    let article = create_article(&server.repository, &user).await;
+
+    let update = realworld_web::articles::update::Request {
+        article: UpdateArticleRequest {
+            tag_list: None,
+            add_tags: vec![],
+            remove_tags: vec![],
+            title: Some("A brand new title".to_string()),
+            description: None,
+            body: None,
+        },
+    };
+    let updated_article = server
+        .update_article(&update, &article.slug, &token)
+        .await
+        .unwrap();
+    assert_eq!(updated_article.article.slug, article.slug);
+    assert_eq!(updated_article.article.title, "A brand new title");
+}
+
+#[tokio::test]
+async fn non_authors_cannot_update_articles_whatever_they_send() {
+    let mut server = TestApp::create("non_authors_cannot_update_articles_whatever_they_send").await;
+    let author = create_user(&server.repository).await.0;
+    let other = create_user(&server.repository).await.0;
+    let article = create_article(&server.repository, &author).await;
+    let invalid = realworld_web::articles::update::Request {
+        article: UpdateArticleRequest {
+            tag_list: None,
+            add_tags: vec![],
+            remove_tags: vec![],
+            title: Some(" ".to_string()),
+            description: None,
+            body: None,
+        },
+    };
+
+    let token = encode_token(&server.repository, &other).await;
+    let response = server
+        .update_article(&invalid, &article.slug, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body: ErrorResponse = response_json(response).await;
+    assert_eq!(body.code, ErrorCode::Forbidden);
+
+    let token = encode_token(&server.repository, &author).await;
+    let response = server
+        .update_article(&invalid, &article.slug, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
 #[tokio::test]
 async fn should_delete_article() {
     let mut server = TestApp::create("should_delete_article").await;
     let user = create_user(&server.repository).await.0;
-    let token = encode_token(user.id);
+    let token = encode_token(&server.repository, &user).await;
     let article = create_article(&server.repository, &user).await;
 
     server
@@ -177,8 +548,243 @@ async fn should_delete_article() {
         .await
         .unwrap();
 
+    // Only the author can delete it, once authenticated
+    let response = server.delete_article(&article.slug, "").await.unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let other = create_user(&server.repository).await.0;
+    let other_token = encode_token(&server.repository, &other).await;
+    let response = server
+        .delete_article(&article.slug, &other_token)
+        .await
+        .unwrap_err();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body: ErrorResponse = response_json(response).await;
+    assert_eq!(body.code, ErrorCode::Forbidden);
+
     server.delete_article(&article.slug, &token).await.unwrap();
 
     let result = server.get_article(&article.slug, Some(&token)).await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn should_paginate_articles() {
+    let mut server = TestApp::create("should_paginate_articles").await;
+    let users = create_users(&server.repository, 5)
+        .await
+        .into_iter()
+        .map(|(u, _)| u)
+        .collect_vec();
+    create_articles(&server.repository, users).await;
+
+    let query = ArticleQuery {
+        limit: Some(2),
+        offset: Some(1),
+        ..Default::default()
+    };
+    let response = server.get_articles(Some(query)).await.unwrap();
+    assert_eq!(response.articles.len(), 2);
+    // The count refers to all the matching articles, not just to the returned page
+    assert_eq!(response.articles_count, 5);
+
+    let query = ArticleQuery {
+        offset: Some(4),
+        ..Default::default()
+    };
+    let response = server.get_articles(Some(query)).await.unwrap();
+    assert_eq!(response.articles.len(), 1);
+    assert_eq!(response.articles_count, 5);
+}
+
+#[tokio::test]
+async fn page_size_is_bounded_by_settings() {
+    let settings = Settings {
+        pagination: Pagination {
+            default_limit: 2,
+            max_limit: 3,
+        },
+        ..Default::default()
+    };
+    let mut server =
+        TestApp::create_with_settings("page_size_is_bounded_by_settings", settings).await;
+    let users = create_users(&server.repository, 5)
+        .await
+        .into_iter()
+        .map(|(u, _)| u)
+        .collect_vec();
+    create_articles(&server.repository, users.clone()).await;
+
+    let response = server.get_articles(None).await.unwrap();
+    assert_eq!(response.articles.len(), 2);
+    assert_eq!(response.articles_count, 5);
+
+    let query = ArticleQuery {
+        limit: Some(50),
+        ..Default::default()
+    };
+    let response = server.get_articles(Some(query)).await.unwrap();
+    assert_eq!(response.articles.len(), 3);
+    assert_eq!(response.articles_count, 5);
+
+    // Same for the feed
+    let reader = users[0].clone();
+    for user in &users[1..] {
+        reader
+            .follow(user.profile.clone(), &server.repository.0)
+            .await
+            .unwrap();
+    }
+    let token = encode_token(&server.repository, &reader).await;
+    let response = server.get_feed(Default::default(), &token).await.unwrap();
+    assert_eq!(response.articles.len(), 2);
+    assert_eq!(response.articles_count, 5);
+
+    let query = FeedQuery {
+        limit: Some(50),
+        offset: Some(1),
+        ..Default::default()
+    };
+    let response = server.get_feed(query, &token).await.unwrap();
+    assert_eq!(response.articles.len(), 3);
+    assert_eq!(response.articles_count, 5);
+}
+
+#[tokio::test]
+async fn should_page_through_the_feed_with_cursors() {
+    let mut server = TestApp::create("should_page_through_the_feed_with_cursors").await;
+    let user = create_user(&server.repository).await.0;
+    let token = encode_token(&server.repository, &user).await;
+    let oldest = create_article(&server.repository, &user).await.slug;
+    let recent = create_article(&server.repository, &user).await.slug;
+
+    let query = FeedQuery {
+        limit: Some(1),
+        ..Default::default()
+    };
+    let response = server.get_feed(query, &token).await.unwrap();
+    assert_eq!(response.articles[0].slug, recent);
+    let cursor = response.next_cursor.unwrap();
+
+    // Unlike offsets, cursors don't move when new articles get published
+    create_article(&server.repository, &user).await;
+    let query = FeedQuery {
+        limit: Some(1),
+        cursor: Some(cursor),
+        ..Default::default()
+    };
+    let response = server.get_feed(query, &token).await.unwrap();
+    assert_eq!(response.articles[0].slug, oldest);
+    assert_eq!(response.articles_count, 3);
+    assert_eq!(response.next_cursor, None);
+
+    let query = FeedQuery {
+        cursor: Some("not-a-cursor".to_string()),
+        ..Default::default()
+    };
+    let response = server.get_feed(query, &token).await.err().unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn invalid_articles_and_comments_are_rejected() {
+    let mut server = TestApp::create("invalid_articles_and_comments_are_rejected").await;
+    let user = create_user(&server.repository).await.0;
+    let token = encode_token(&server.repository, &user).await;
+
+    let request = realworld_web::articles::insert::Request {
+        article: NewArticleRequest {
+            title: " ".to_string(),
+            description: "A description".to_string(),
+            body: "A body".to_string(),
+            tag_list: Some(vec!["".to_string()]),
+        },
+    };
+    let response = server.create_article(&request, &token).await.err().unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "errors": {
+                "tagList": ["can't contain blank tags"],
+                "title": ["can't be blank"],
+            },
+            "code": "invalid_input",
+        })
+    );
+
+    let slug = create_article(&server.repository, &user).await.slug;
+    let request = realworld_web::comments::create::Request {
+        comment: realworld_web::comments::create::NewCommentRequest {
+            body: "".to_string(),
+            parent_id: None,
+        },
+    };
+    let response = server
+        .create_comment(&slug, &request, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn articles_with_the_same_title_get_distinct_slugs() {
+    let mut server = TestApp::create("articles_with_the_same_title_get_distinct_slugs").await;
+    let user = create_user(&server.repository).await.0;
+    let token = encode_token(&server.repository, &user).await;
+
+    let request = realworld_web::articles::insert::Request {
+        article: NewArticleRequest {
+            title: "Où est la bibliothèque?".to_string(),
+            description: "A description".to_string(),
+            body: "A body".to_string(),
+            tag_list: None,
+        },
+    };
+    let first = server.create_article(&request, &token).await.unwrap();
+    let second = server.create_article(&request, &token).await.unwrap();
+    assert_eq!(first.article.slug, "ou-est-la-bibliotheque");
+    assert_eq!(second.article.slug, "ou-est-la-bibliotheque-2");
+
+    let retrieved = server
+        .get_article(&second.article.slug, Some(&token))
+        .await
+        .unwrap();
+    assert_eq!(retrieved.article.title, request.article.title);
+}
+
+#[tokio::test]
+async fn malformed_requests_are_rejected_with_error_codes() {
+    let mut server = TestApp::create("malformed_requests_are_rejected_with_error_codes").await;
+    let user = create_user(&server.repository).await.0;
+    let token = encode_token(&server.repository, &user).await;
+    let slug = create_article(&server.repository, &user).await.slug;
+
+    let get = |url: &str| Request::get(url).body(Body::empty()).unwrap();
+    let post = |url: &str, body: &str| {
+        Request::post(url)
+            .header("Authorization", format!("token: {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let delete = Request::delete(format!("/api/articles/{}/comments/abc", slug))
+        .header("Authorization", format!("token: {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let requests = [
+        get("/api/articles?sort=bogus"),
+        get("/api/articles?limit=abc"),
+        post("/api/articles", "{"),
+        post("/api/articles", r#"{"article": {"title": "A title"}}"#),
+        delete,
+    ];
+    for request in requests {
+        let response = server.send(request).await;
+        assert!(response.status().is_client_error());
+        let body: ErrorResponse = response_json(response).await;
+        assert_eq!(body.code, ErrorCode::InvalidInput);
+        assert_eq!(body.errors["body"].len(), 1);
+    }
+}
@@ -3,24 +3,28 @@
 mod helpers;
 
 use axum::http::StatusCode;
-use helpers::test_server::TestApp;
+use axum::response::Response;
+use helpers::encode_token;
+use helpers::test_server::{response_json, TestApp};
 use helpers::{create_article, create_user, create_users};
 
 use fake::fake;
 use itertools::Itertools;
-use realworld_web::auth::encode_token;
 use realworld_web::comments::create::NewCommentRequest;
+use realworld_web::comments::update::UpdateCommentRequest;
+use realworld_web::errors::{ErrorCode, ErrorResponse};
 
 #[tokio::test]
 async fn comments_api() {
     let mut server = TestApp::create("comments_api").await;
     let user = create_user(&server.repository).await.0;
     let article = create_article(&server.repository, &user).await;
-    let token = encode_token(user.id);
+    let token = encode_token(&server.repository, &user).await;
 
     let request = realworld_web::comments::create::Request {
         comment: NewCommentRequest {
             body: fake!(Lorem.paragraph(10, 5)),
+            parent_id: None,
         },
     };
     let first_comment = server
@@ -32,12 +36,13 @@ async fn comments_api() {
     assert_eq!(first_comment.comment.author.bio, user.profile.bio);
     assert_eq!(first_comment.comment.author.image, user.profile.image);
     // A user follows themselves, by definition
-    assert_eq!(first_comment.comment.author.following, true);
+    assert!(first_comment.comment.author.following);
 
     // A user can create more than one comment for the same article
     let request = realworld_web::comments::create::Request {
         comment: NewCommentRequest {
             body: fake!(Lorem.paragraph(10, 5)),
+            parent_id: None,
         },
     };
     let second_comment = server
@@ -90,10 +95,11 @@ async fn you_cannot_delete_a_comment_which_you_did_not_write() {
     let article = create_article(&server.repository, &article_author).await;
 
     // comment_author write a comment
-    let token = encode_token(comment_author.id);
+    let token = encode_token(&server.repository, &comment_author).await;
     let request = realworld_web::comments::create::Request {
         comment: NewCommentRequest {
             body: fake!(Lorem.paragraph(10, 5)),
+            parent_id: None,
         },
     };
     let comment = server
@@ -102,10 +108,308 @@ async fn you_cannot_delete_a_comment_which_you_did_not_write() {
         .unwrap();
 
     // article_author tries to delete it
-    let token = encode_token(article_author.id);
+    let token = encode_token(&server.repository, &article_author).await;
     let response = server
         .delete_comment(&article.slug, &comment.comment.id, &token)
         .await;
     assert!(response.is_err());
-    assert_eq!(StatusCode::UNAUTHORIZED, response.unwrap_err().status());
+    let response = response.unwrap_err();
+    assert_eq!(StatusCode::FORBIDDEN, response.status());
+    let body: ErrorResponse = response_json(response).await;
+    assert_eq!(body.code, ErrorCode::Forbidden);
+    assert_eq!(
+        body.errors["body"],
+        ["Only the author can change the comment"]
+    );
+}
+
+#[tokio::test]
+async fn only_authors_can_edit_their_comments() {
+    let mut server = TestApp::create("only_authors_can_edit_their_comments").await;
+    let mut users = create_users(&server.repository, 2)
+        .await
+        .into_iter()
+        .map(|(u, _)| u)
+        .collect_vec();
+    let article_author = users.pop().unwrap();
+    let comment_author = users.pop().unwrap();
+    let article = create_article(&server.repository, &article_author).await;
+
+    let token = encode_token(&server.repository, &comment_author).await;
+    let request = realworld_web::comments::create::Request {
+        comment: NewCommentRequest {
+            body: fake!(Lorem.paragraph(10, 5)),
+            parent_id: None,
+        },
+    };
+    let comment = server
+        .create_comment(&article.slug, &request, &token)
+        .await
+        .unwrap()
+        .comment;
+    assert!(!comment.edited);
+
+    let request = realworld_web::comments::update::Request {
+        comment: UpdateCommentRequest {
+            body: "Edited".to_string(),
+        },
+    };
+    let edited = server
+        .update_comment(&article.slug, &comment.id, &request, &token)
+        .await
+        .unwrap()
+        .comment;
+    assert_eq!(edited.body, "Edited");
+    assert!(edited.edited);
+    assert!(edited.updated_at > comment.updated_at);
+    let comments = server
+        .get_comments(&article.slug, Some(&token))
+        .await
+        .unwrap();
+    assert_eq!(comments.comments, [edited]);
+
+    // Edits are validated like new comments
+    let blank = realworld_web::comments::update::Request {
+        comment: UpdateCommentRequest {
+            body: " ".to_string(),
+        },
+    };
+    let response = server
+        .update_comment(&article.slug, &comment.id, &blank, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    // The article author cannot edit it
+    let token = encode_token(&server.repository, &article_author).await;
+    let response = server
+        .update_comment(&article.slug, &comment.id, &request, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = server
+        .update_comment(&article.slug, &(comment.id + 1), &request, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn replies_are_threaded() {
+    let mut server = TestApp::create("replies_are_threaded").await;
+    let user = create_user(&server.repository).await.0;
+    let article = create_article(&server.repository, &user).await;
+    let other_article = create_article(&server.repository, &user).await;
+    let token = encode_token(&server.repository, &user).await;
+
+    let first = post_comment(&mut server, &article.slug, &token, None)
+        .await
+        .unwrap();
+    let second = post_comment(&mut server, &article.slug, &token, None)
+        .await
+        .unwrap();
+    let reply = post_comment(&mut server, &article.slug, &token, Some(first))
+        .await
+        .unwrap();
+    let nested_reply = post_comment(&mut server, &article.slug, &token, Some(reply))
+        .await
+        .unwrap();
+    // Replies stay on the article of their parent
+    let response = post_comment(&mut server, &other_article.slug, &token, Some(first))
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let comments = server
+        .get_comments(&article.slug, None)
+        .await
+        .unwrap()
+        .comments;
+    let thread = comments
+        .iter()
+        .map(|c| (c.id, c.parent_id, c.depth))
+        .collect_vec();
+    assert_eq!(
+        thread,
+        [
+            (first, None, 0),
+            (reply, Some(first), 1),
+            (nested_reply, Some(reply), 2),
+            (second, None, 0)
+        ]
+    );
+
+    let tree = server
+        .query_comments(&article.slug, "format=tree")
+        .await
+        .unwrap()
+        .comments;
+    assert_eq!(tree.iter().map(|c| c.id).collect_vec(), [first, second]);
+    assert_eq!(tree[0].replies[0].id, reply);
+    assert_eq!(tree[0].replies[0].replies[0].id, nested_reply);
+
+    // Comments with replies are emptied rather than deleted
+    server
+        .delete_comment(&article.slug, &first, &token)
+        .await
+        .unwrap();
+    let tree = server
+        .query_comments(&article.slug, "format=tree")
+        .await
+        .unwrap()
+        .comments;
+    assert!(tree[0].deleted);
+    assert_eq!(tree[0].body, "");
+    assert_eq!(tree[0].replies[0].id, reply);
+    let response = server
+        .delete_comment(&article.slug, &first, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = post_comment(&mut server, &article.slug, &token, Some(first))
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Others are gone for good
+    server
+        .delete_comment(&article.slug, &second, &token)
+        .await
+        .unwrap();
+    let tree = server
+        .query_comments(&article.slug, "format=tree")
+        .await
+        .unwrap()
+        .comments;
+    assert_eq!(tree.iter().map(|c| c.id).collect_vec(), [first]);
+}
+
+async fn post_comment(
+    server: &mut TestApp,
+    slug: &str,
+    token: &str,
+    parent_id: Option<u64>,
+) -> Result<u64, Response> {
+    let request = realworld_web::comments::create::Request {
+        comment: NewCommentRequest {
+            body: fake!(Lorem.sentence(3, 5)),
+            parent_id,
+        },
+    };
+    let response = server.create_comment(slug, &request, token).await?;
+    Ok(response.comment.id)
+}
+
+#[tokio::test]
+async fn comments_are_paginated() {
+    let mut server = TestApp::create("comments_are_paginated").await;
+    let user = create_user(&server.repository).await.0;
+    let article = create_article(&server.repository, &user).await;
+    let token = encode_token(&server.repository, &user).await;
+    let mut ids = vec![];
+    for _ in 0..3 {
+        ids.push(
+            post_comment(&mut server, &article.slug, &token, None)
+                .await
+                .unwrap(),
+        );
+    }
+
+    let page = server
+        .query_comments(&article.slug, "order=desc&limit=2")
+        .await
+        .unwrap();
+    assert_eq!(
+        page.comments.iter().map(|c| c.id).collect_vec(),
+        [ids[2], ids[1]]
+    );
+    assert_eq!(page.threads_count, 3);
+    assert_eq!(page.next_cursor, Some(ids[1]));
+
+    let query_string = format!("order=desc&limit=2&cursor={}", ids[1]);
+    let page = server
+        .query_comments(&article.slug, &query_string)
+        .await
+        .unwrap();
+    assert_eq!(page.comments.iter().map(|c| c.id).collect_vec(), [ids[0]]);
+    assert_eq!(page.next_cursor, None);
+
+    let page = server
+        .query_comments(&article.slug, "offset=1")
+        .await
+        .unwrap();
+    assert_eq!(
+        page.comments.iter().map(|c| c.id).collect_vec(),
+        [ids[1], ids[2]]
+    );
+
+    // Replies are capped per thread, the full count telling how many were left out
+    let mut replies = vec![];
+    for _ in 0..2 {
+        let reply = post_comment(&mut server, &article.slug, &token, Some(ids[0])).await;
+        replies.push(reply.unwrap());
+    }
+    let page = server
+        .query_comments(&article.slug, "limit=1&replies=1")
+        .await
+        .unwrap();
+    assert_eq!(
+        page.comments.iter().map(|c| c.id).collect_vec(),
+        [ids[0], replies[0]]
+    );
+    assert_eq!(page.comments[0].replies_count, Some(2));
+    assert_eq!(page.comments[1].replies_count, None);
+    assert_eq!(page.threads_count, 3);
+}
+
+#[tokio::test]
+async fn comments_are_only_reachable_through_their_article() {
+    let mut server = TestApp::create("comments_are_only_reachable_through_their_article").await;
+    let user = create_user(&server.repository).await.0;
+    let article = create_article(&server.repository, &user).await;
+    let other_article = create_article(&server.repository, &user).await;
+    let token = encode_token(&server.repository, &user).await;
+    let comment = post_comment(&mut server, &article.slug, &token, None)
+        .await
+        .unwrap();
+
+    let response = server
+        .delete_comment(&other_article.slug, &comment, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = server
+        .delete_comment("missing-article", &comment, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let request = realworld_web::comments::update::Request {
+        comment: UpdateCommentRequest {
+            body: "Edited".to_string(),
+        },
+    };
+    let response = server
+        .update_comment(&other_article.slug, &comment, &request, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // The comment is left untouched
+    let comments = server
+        .get_comments(&article.slug, None)
+        .await
+        .unwrap()
+        .comments;
+    assert_eq!(comments.iter().map(|c| c.id).collect_vec(), [comment]);
+    assert!(!comments[0].edited);
 }
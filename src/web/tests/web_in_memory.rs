@@ -0,0 +1,61 @@
+// The app only needs a `Repository`: this workflow runs against the in-memory
+// one, so it needs no database.
+
+mod helpers;
+
+use helpers::generate;
+use helpers::test_server::TestApp;
+
+use realworld_web::articles::insert::NewArticleRequest;
+use realworld_web::comments::create::NewCommentRequest;
+
+#[tokio::test]
+async fn app_runs_on_the_in_memory_repository() {
+    let mut server = TestApp::in_memory();
+    let (user, password) = generate::new_user();
+
+    server.register_user(&user, &password).await.unwrap();
+    let token = server
+        .login_user(&user.email, &password)
+        .await
+        .unwrap()
+        .user
+        .token;
+    let current = server.get_current_user(&token).await.unwrap();
+    assert_eq!(current.user.username, user.username);
+
+    let content = generate::article_content();
+    let request = realworld_web::articles::insert::Request {
+        article: NewArticleRequest {
+            title: content.title.clone(),
+            description: content.description.clone(),
+            body: content.body.clone(),
+            tag_list: Some(content.tag_list.clone()),
+        },
+    };
+    let article = server
+        .create_article(&request, &token)
+        .await
+        .unwrap()
+        .article;
+    assert_eq!(article.title, content.title);
+    assert_eq!(article.author.username, user.username);
+
+    let fetched = server.get_article(&article.slug, None).await.unwrap();
+    assert_eq!(fetched.article.slug, article.slug);
+
+    let request = realworld_web::comments::create::Request {
+        comment: NewCommentRequest {
+            body: "In memory".to_string(),
+            parent_id: None,
+        },
+    };
+    let comment = server
+        .create_comment(&article.slug, &request, &token)
+        .await
+        .unwrap()
+        .comment;
+    let comments = server.get_comments(&article.slug, None).await.unwrap();
+    let bodies: Vec<_> = comments.comments.iter().map(|c| (c.id, &c.body)).collect();
+    assert_eq!(bodies, vec![(comment.id, &comment.body)]);
+}
@@ -3,10 +3,10 @@
 mod helpers;
 
 use helpers::create_users;
+use helpers::encode_token;
 use helpers::test_server::TestApp;
 
 use itertools::Itertools;
-use realworld_web::auth::encode_token;
 
 #[tokio::test]
 async fn profiles_api() {
@@ -29,39 +29,39 @@ async fn profiles_api() {
     );
     assert_eq!(followed_profile.profile.bio, followed_user.profile.bio);
     assert_eq!(followed_profile.profile.image, followed_user.profile.image);
-    assert_eq!(followed_profile.profile.following, false);
+    assert!(!followed_profile.profile.following);
 
-    let follower_token = encode_token(follower_user.id);
+    let follower_token = encode_token(&server.repository, &follower_user).await;
     let followed_profile = server
         .follow_profile(&followed_user.profile.username, &follower_token)
         .await
         .unwrap();
-    assert_eq!(followed_profile.profile.following, true);
+    assert!(followed_profile.profile.following);
 
     // If not logged in, following is still false
     let followed_profile = server
         .get_profile(&followed_user.profile.username, None)
         .await
         .unwrap();
-    assert_eq!(followed_profile.profile.following, false);
+    assert!(!followed_profile.profile.following);
 
     // If logged in, following is correctly valued
     let followed_profile = server
         .get_profile(&followed_user.profile.username, Some(&follower_token))
         .await
         .unwrap();
-    assert_eq!(followed_profile.profile.following, true);
+    assert!(followed_profile.profile.following);
 
     let unfollowed_profile = server
         .unfollow_profile(&followed_user.profile.username, &follower_token)
         .await
         .unwrap();
-    assert_eq!(unfollowed_profile.profile.following, false);
+    assert!(!unfollowed_profile.profile.following);
 
     // After unfollowing, following is now false
     let p = server
         .get_profile(&followed_user.profile.username, Some(&follower_token))
         .await
         .unwrap();
-    assert_eq!(p.profile.following, false);
+    assert!(!p.profile.following);
 }
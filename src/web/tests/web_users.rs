@@ -1,8 +1,14 @@
 mod helpers;
 
 use helpers::generate;
-use helpers::test_server::TestApp;
+use helpers::test_server::{response_json, TestApp};
 
+use domain::repositories::Repository;
+
+use axum::http::StatusCode;
+use realworld_web::auth::Jwt;
+use realworld_web::errors::{ErrorCode, ErrorResponse};
+use realworld_web::settings::{Authentication, Settings};
 use realworld_web::users::responses::UserResponse;
 use realworld_web::users::update::UpdateUserRequest;
 
@@ -24,6 +30,41 @@ async fn register_and_login() {
     assert_eq!(user_details.user.email, user.email);
 }
 
+#[tokio::test]
+async fn login_failures_are_indistinguishable() {
+    let mut server = TestApp::create("login_failures_are_indistinguishable").await;
+    let (user, password) = generate::new_user();
+    server.register_user(&user, &password).await.unwrap();
+
+    // An account whose stored hash cannot be processed by bcrypt
+    let (broken, broken_password) = generate::new_user();
+    let sign_up = domain::SignUp {
+        username: broken.username,
+        email: broken.email.clone(),
+        password: domain::Password::from_hash("not-a-bcrypt-hash".to_string()),
+    };
+    server.repository.0.sign_up(sign_up).await.unwrap();
+
+    let wrong_password = format!("not-{}", password);
+    let attempts = [
+        (user.email.as_str(), wrong_password.as_str()),
+        ("nobody@example.com", password.as_str()),
+        (broken.email.as_str(), broken_password.as_str()),
+    ];
+    for (email, password) in attempts {
+        let response = server.login_user(email, password).await.err().unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body: serde_json::Value = response_json(response).await;
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "errors": {"body": ["Invalid credentials"]},
+                "code": "invalid_credentials",
+            })
+        );
+    }
+}
+
 #[tokio::test]
 async fn update_and_retrieve_user_details() {
     let mut server = TestApp::create("update_and_retrieve_user_details").await;
@@ -59,4 +100,255 @@ async fn update_and_retrieve_user_details() {
     let current_user: UserResponse = server.get_current_user(&token).await.unwrap();
     assert_eq!(current_user.user.bio, new_details.user.bio);
     assert_eq!(current_user.user.image, new_details.user.image);
+
+    // Clients clear the image by sending an empty one
+    let cleared = realworld_web::users::update::Request {
+        user: UpdateUserRequest {
+            image: Some("".to_string()),
+            ..new_details.user
+        },
+    };
+    let updated_user = server.update_user_details(&cleared, &token).await.unwrap();
+    assert_eq!(updated_user.user.image, None);
+    assert_eq!(updated_user.user.bio, cleared.user.bio);
+}
+
+async fn session(server: &TestApp, user: &domain::User) -> uuid::Uuid {
+    let (session, _) =
+        domain::Session::start(user, chrono::Duration::days(1), &server.repository.0)
+            .await
+            .unwrap();
+    session.id
+}
+
+#[tokio::test]
+async fn tokens_signed_with_previous_secrets_are_accepted() {
+    let authentication = |secret: &str, verification_keys: &[&str]| Authentication {
+        signing_key: secret.to_string(),
+        verification_keys: verification_keys.iter().map(|k| k.to_string()).collect(),
+        issuer: Some("realworld".to_string()),
+        ..Default::default()
+    };
+    let settings = Settings {
+        authentication: authentication("new", &["new", "old"]),
+        ..Default::default()
+    };
+    let mut server =
+        TestApp::create_with_settings("tokens_signed_with_previous_secrets_are_accepted", settings)
+            .await;
+    let (user, password) = generate::new_user();
+    let stored_user = server.repository.0.sign_up(user.clone()).await.unwrap();
+    let (user_id, session_id) = (stored_user.id, session(&server, &stored_user).await);
+
+    let token = server
+        .login_user(&user.email, &password)
+        .await
+        .unwrap()
+        .user
+        .token;
+    assert!(server.get_current_user(&token).await.is_ok());
+
+    let old = Jwt::new(&authentication("old", &["old"])).unwrap();
+    let token = old.encode_token(user_id, session_id);
+    let user_details = server.get_current_user(&token).await.unwrap();
+    assert_eq!(user_details.user.username, user.username);
+
+    let unknown = Jwt::new(&authentication("unknown", &["unknown"])).unwrap();
+    let token = unknown.encode_token(user_id, session_id);
+    let response = server.get_current_user(&token).await.unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body: ErrorResponse = response_json(response).await;
+    assert_eq!(body.code, ErrorCode::Unauthorized);
+    assert_eq!(body.errors["body"], ["Missing or invalid credentials"]);
+
+    // Right key, wrong issuer
+    let no_issuer = Jwt::new(&Authentication {
+        issuer: None,
+        ..authentication("new", &["new"])
+    })
+    .unwrap();
+    let token = no_issuer.encode_token(user_id, session_id);
+    let response = server.get_current_user(&token).await.unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn refresh_tokens_can_be_used_once() {
+    let mut server = TestApp::create("refresh_tokens_can_be_used_once").await;
+    let (user, password) = generate::new_user();
+
+    let registered = server.register_user(&user, &password).await.unwrap().user;
+    let refresh_token = registered.refresh_token.unwrap();
+
+    let refreshed = server.refresh_token(&refresh_token).await.unwrap().user;
+    assert_eq!(refreshed.username, user.username);
+    let new_refresh_token = refreshed.refresh_token.unwrap();
+    assert_ne!(new_refresh_token, refresh_token);
+    assert!(server.get_current_user(&refreshed.token).await.is_ok());
+
+    // Refresh tokens are rotated: the old one is no longer valid
+    let response = server.refresh_token(&refresh_token).await.unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(server.refresh_token(&new_refresh_token).await.is_ok());
+
+    let response = server.refresh_token("made-up").await.unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn logout_revokes_the_current_session() {
+    let mut server = TestApp::create("logout_revokes_the_current_session").await;
+    let (user, password) = generate::new_user();
+    server.register_user(&user, &password).await.unwrap();
+
+    let first = server
+        .login_user(&user.email, &password)
+        .await
+        .unwrap()
+        .user;
+    let second = server
+        .login_user(&user.email, &password)
+        .await
+        .unwrap()
+        .user;
+
+    server.logout(&first.token).await.unwrap();
+
+    let response = server.get_current_user(&first.token).await.unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = server
+        .refresh_token(first.refresh_token.as_ref().unwrap())
+        .await
+        .unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Other sessions are not affected
+    assert!(server.get_current_user(&second.token).await.is_ok());
+    assert!(server
+        .refresh_token(second.refresh_token.as_ref().unwrap())
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn logout_all_revokes_every_session() {
+    let mut server = TestApp::create("logout_all_revokes_every_session").await;
+    let (user, password) = generate::new_user();
+    server.register_user(&user, &password).await.unwrap();
+    let (other_user, other_password) = generate::new_user();
+    let other = server
+        .register_user(&other_user, &other_password)
+        .await
+        .unwrap()
+        .user;
+
+    let first = server
+        .login_user(&user.email, &password)
+        .await
+        .unwrap()
+        .user;
+    let second = server
+        .login_user(&user.email, &password)
+        .await
+        .unwrap()
+        .user;
+
+    server.logout_all(&second.token).await.unwrap();
+
+    for session in [&first, &second] {
+        let response = server.get_current_user(&session.token).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = server
+            .refresh_token(session.refresh_token.as_ref().unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    // Logging in again opens a new session
+    let third = server
+        .login_user(&user.email, &password)
+        .await
+        .unwrap()
+        .user;
+    assert!(server.get_current_user(&third.token).await.is_ok());
+    // Other users are not affected
+    assert!(server.get_current_user(&other.token).await.is_ok());
+}
+
+#[tokio::test]
+async fn invalid_registrations_are_rejected() {
+    let mut server = TestApp::create("invalid_registrations_are_rejected").await;
+    let (mut user, _) = generate::new_user();
+    user.email = "not-an-email".to_string();
+
+    let response = server.register_user(&user, "short").await.unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "errors": {
+                "email": ["is invalid"],
+                "password": ["is too short (minimum is 8 characters)"],
+            },
+            "code": "invalid_input",
+        })
+    );
+}
+
+#[tokio::test]
+async fn usernames_and_emails_cannot_be_taken_twice() {
+    let mut server = TestApp::create("usernames_and_emails_cannot_be_taken_twice").await;
+    let (user, password) = generate::new_user();
+    server.register_user(&user, &password).await.unwrap();
+
+    let (mut other, other_password) = generate::new_user();
+    other.email = user.email.clone();
+    let response = server
+        .register_user(&other, &other_password)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "errors": {"email": ["has already been taken"]},
+            "code": "email_taken",
+        })
+    );
+
+    let (other, other_password) = generate::new_user();
+    server.register_user(&other, &other_password).await.unwrap();
+    let token = server
+        .login_user(&other.email, &other_password)
+        .await
+        .unwrap()
+        .user
+        .token;
+    let request = realworld_web::users::update::Request {
+        user: UpdateUserRequest {
+            bio: None,
+            image: None,
+            email: None,
+            password: None,
+            username: Some(user.username.clone()),
+        },
+    };
+    let response = server
+        .update_user_details(&request, &token)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "errors": {"username": ["has already been taken"]},
+            "code": "username_taken",
+        })
+    );
 }